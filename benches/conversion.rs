@@ -0,0 +1,95 @@
+//! Compares the consuming and borrowing conversions of streamer messages to blocks.
+//!
+//! Before the timings, the allocations made by one conversion of each kind are printed,
+//! the consuming conversion moving payloads such as contract code and function call args
+//! instead of copying them. The `headers` shape is dominated by block and chunk headers,
+//! with hundreds of approvals and validator proposals.
+
+#[allow(dead_code, unused_imports)]
+#[path = "../src/codec/mod.rs"]
+mod codec;
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use common::alloc::CountingAllocator;
+use common::message::{message, Shape};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator::new();
+
+const SHAPES: &[(&str, Shape)] = &[
+    (
+        "calls",
+        Shape {
+            shards: 4,
+            transactions: 200,
+            args_size: 512,
+            deployments: 0,
+            code_size: 0,
+            approvals: 2,
+            proposals: 0,
+        },
+    ),
+    (
+        "deployments",
+        Shape {
+            shards: 4,
+            transactions: 10,
+            args_size: 64,
+            deployments: 4,
+            code_size: 1 << 20,
+            approvals: 2,
+            proposals: 0,
+        },
+    ),
+    (
+        "headers",
+        Shape {
+            shards: 16,
+            transactions: 0,
+            args_size: 0,
+            deployments: 0,
+            code_size: 0,
+            approvals: 400,
+            proposals: 50,
+        },
+    ),
+];
+
+fn report_allocations() {
+    for (name, shape) in SHAPES {
+        let sm = message(*shape);
+        let (_, borrowing) = ALLOCATOR.measure(|| codec::Block::from(&sm));
+        let (_, consuming) = ALLOCATOR.measure(|| codec::Block::from(sm));
+
+        println!(
+            "{}: borrowing {} allocations of {} bytes, consuming {} allocations of {} bytes",
+            name,
+            borrowing.allocations,
+            borrowing.allocated,
+            consuming.allocations,
+            consuming.allocated
+        );
+    }
+}
+
+fn conversion(c: &mut Criterion) {
+    report_allocations();
+
+    let mut group = c.benchmark_group("streamer_message_to_block");
+    for (name, shape) in SHAPES {
+        let sm = message(*shape);
+
+        group.bench_with_input(BenchmarkId::new("borrowing", name), &sm, |b, sm| {
+            b.iter(|| codec::Block::from(sm))
+        });
+        group.bench_with_input(BenchmarkId::new("consuming", name), &sm, |b, sm| {
+            b.iter_batched(|| sm.clone(), codec::Block::from, BatchSize::LargeInput)
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, conversion);
+criterion_main!(benches);
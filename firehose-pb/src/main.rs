@@ -1,7 +1,158 @@
+const BYTES_FIELDS: &[&str] = &[
+    ".sf.near.type.v1.Block.filter_hash",
+    ".sf.near.type.v1.BlockHeader.epoch_sync_data_hash",
+    ".sf.near.type.v1.Signature.bytes",
+    ".sf.near.type.v1.PublicKey.bytes",
+    ".sf.near.type.v1.ChunkHeader.chunk_hash",
+    ".sf.near.type.v1.ChunkHeader.prev_block_hash",
+    ".sf.near.type.v1.ChunkHeader.outcome_root",
+    ".sf.near.type.v1.ChunkHeader.prev_state_root",
+    ".sf.near.type.v1.ChunkHeader.encoded_merkle_root",
+    ".sf.near.type.v1.ChunkHeader.outgoing_receipts_root",
+    ".sf.near.type.v1.ChunkHeader.tx_root",
+    ".sf.near.type.v1.StateChangeValue.DataUpdate.key",
+    ".sf.near.type.v1.StateChangeValue.DataUpdate.value",
+    ".sf.near.type.v1.StateChangeValue.DataDeletion.key",
+    ".sf.near.type.v1.StateChangeValue.ContractCodeUpdate.code",
+    ".sf.near.type.v1.StateChangeValue.ContractCodeUpdate.code_hash",
+    ".sf.near.type.v1.SuccessValueExecutionStatus.value",
+    ".sf.near.type.v1.DeployContractAction.code",
+    ".sf.near.type.v1.DeployContractAction.code_hash",
+    ".sf.near.type.v1.FunctionCallAction.args",
+    ".sf.near.type.v1.TransactionFinalized.value",
+    ".sf.near.type.v1.TrimmedPayload.hash",
+];
+
+const OPTIONAL_BYTES_FIELDS: &[&str] = &[".sf.near.type.v1.ReceiptData.data"];
+
+const REPEATED_BYTES_FIELDS: &[&str] = &[".sf.near.type.v1.ChunkEndorsements.bitmaps"];
+
+const ONEOF_FIELDS: &[&str] = &[
+    ".sf.near.type.v1.StateChangeCause.cause",
+    ".sf.near.type.v1.StateChangeValue.value",
+    ".sf.near.type.v1.Receipt.receipt",
+    ".sf.near.type.v1.ExecutionOutcome.status",
+    ".sf.near.type.v1.FailureExecutionStatus.failure",
+    ".sf.near.type.v1.ActionError.kind",
+    ".sf.near.type.v1.Action.action",
+    ".sf.near.type.v1.AccessKeyPermission.permission",
+];
+
+/// Enumeration fields and their enum, serialized by name in JSON.
+const ENUM_FIELDS: &[(&str, &str)] = &[
+    (".sf.near.type.v1.Signature.type", "CurveKind"),
+    (".sf.near.type.v1.PublicKey.type", "CurveKind"),
+    (
+        ".sf.near.type.v1.ExecutionOutcome.metadata",
+        "ExecutionMetadata",
+    ),
+    (
+        ".sf.near.type.v1.FailureExecutionStatus.failure.invalid_tx_error",
+        "InvalidTxError",
+    ),
+    (
+        ".sf.near.type.v1.FunctionCallErrorKind.error",
+        "FunctionCallErrorSer",
+    ),
+    (
+        ".sf.near.type.v1.NewReceiptValidationErrorKind.error",
+        "ReceiptValidationError",
+    ),
+    (
+        ".sf.near.type.v1.DelegateActionAccessKeyErrorKind.error",
+        "InvalidTxError",
+    ),
+    (".sf.near.type.v1.MerklePathItem.direction", "Direction"),
+    (".sf.near.type.v1.FtTransfer.kind", "FtTransferKind"),
+    (".sf.near.type.v1.FtTransfer.status", "ReceiptStatus"),
+    (".sf.near.type.v1.NftActivity.kind", "NftActivityKind"),
+    (".sf.near.type.v1.NftActivity.status", "ReceiptStatus"),
+    (
+        ".sf.near.type.v1.TransactionFinalized.status",
+        "ReceiptStatus",
+    ),
+    (".sf.near.type.v1.BalanceChange.cause", "BalanceChangeCause"),
+    (".sf.near.type.v1.DecodedArgs.decoding", "ArgsDecoding"),
+];
+
+const OUTPUT: &str = "./src/codec/sf.near.r#type.v1.rs";
+
 fn main() {
-    tonic_build::configure()
+    let mut builder = tonic_build::configure()
         .out_dir("./src/codec")
         .format(true)
-        .compile(&["sf/near/type/v1/type.proto"], &["../sf-near/proto/"])
+        .type_attribute(".", "#[derive(::serde::Serialize, ::serde::Deserialize)]")
+        .type_attribute(".", "#[serde(rename_all = \"camelCase\")]")
+        .type_attribute(
+            ".sf.near.type.v1.CryptoHash",
+            "#[serde(into = \"String\", try_from = \"String\")]",
+        )
+        .type_attribute(
+            ".sf.near.type.v1.BigInt",
+            "#[serde(into = \"String\", try_from = \"String\")]",
+        );
+
+    for field in BYTES_FIELDS {
+        builder = builder.field_attribute(field, "#[serde(with = \"crate::codec::json::bytes\")]");
+    }
+    for field in OPTIONAL_BYTES_FIELDS {
+        builder = builder.field_attribute(
+            field,
+            "#[serde(default, with = \"crate::codec::json::optional_bytes\")]",
+        );
+    }
+    for field in REPEATED_BYTES_FIELDS {
+        builder = builder.field_attribute(
+            field,
+            "#[serde(with = \"crate::codec::json::repeated_bytes\")]",
+        );
+    }
+    for field in ONEOF_FIELDS {
+        builder = builder.field_attribute(field, "#[serde(flatten)]");
+    }
+    for (field, enumeration) in ENUM_FIELDS {
+        let function = |name| {
+            format!(
+                "crate::codec::json::enumeration::{}::<crate::codec::{}, _>",
+                name, enumeration
+            )
+        };
+        builder = builder.field_attribute(
+            field,
+            format!(
+                "#[serde(serialize_with = \"{}\", deserialize_with = \"{}\")]",
+                function("serialize"),
+                function("deserialize"),
+            ),
+        );
+    }
+
+    builder
+        .compile(&["sf/near/type/v1/type.proto"], &["./proto/"])
         .unwrap_or_else(|e| panic!("Failed to compile near NEAR firehose proto(s) {:?}", e));
+
+    default_missing_fields();
+}
+
+/// Lets messages parse from JSON missing some of their fields, like protobuf does.
+///
+/// Type attributes also apply to the oneof enums nested in a message, so the attribute
+/// is added to the generated messages instead.
+fn default_missing_fields() {
+    const MESSAGE_DERIVE: &str = "#[derive(Clone, PartialEq, ::prost::Message)]";
+
+    let generated = std::fs::read_to_string(OUTPUT)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {:?}", OUTPUT, e));
+    let mut patched = String::with_capacity(generated.len());
+    for line in generated.lines() {
+        patched.push_str(line);
+        patched.push('\n');
+        if line.trim_start() == MESSAGE_DERIVE {
+            let indent = &line[..line.len() - MESSAGE_DERIVE.len()];
+            patched.push_str(indent);
+            patched.push_str("#[serde(default)]\n");
+        }
+    }
+    std::fs::write(OUTPUT, patched)
+        .unwrap_or_else(|e| panic!("Failed to write {}: {:?}", OUTPUT, e));
 }
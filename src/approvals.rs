@@ -0,0 +1,111 @@
+use near_client::{GetBlock, GetValidatorOrdered, ViewClientActor};
+use near_indexer::near_primitives::hash::CryptoHash;
+use near_indexer::near_primitives::types::{AccountId, BlockId, BlockReference};
+use near_indexer::near_primitives::views::BlockHeaderView;
+use near_o11y::WithSpanContextExt;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Number of epochs whose block producers are kept, a block needs its parent's epoch and
+/// at most the following one.
+const CACHED_EPOCHS: usize = 2;
+
+/// Pairs block approvals with the account of the block producer that signed them.
+///
+/// Approvals of a block are made by the block producers of its parent's epoch, in the
+/// order nearcore reports them. The last block of an epoch also needs approvals from
+/// the next epoch block producers, those not already present are appended after them.
+///
+/// Block producers are fetched once per epoch and the epoch of the parent is taken from
+/// the previous block, so the view client is only queried at epoch boundaries and after
+/// a gap or a fork.
+pub(crate) struct ApprovalResolver {
+    view_client: actix::Addr<ViewClientActor>,
+    last_block: Option<(CryptoHash, CryptoHash)>,
+    producers: VecDeque<(CryptoHash, Arc<Vec<AccountId>>)>,
+}
+
+impl ApprovalResolver {
+    pub fn new(view_client: actix::Addr<ViewClientActor>) -> Self {
+        ApprovalResolver {
+            view_client,
+            last_block: None,
+            producers: VecDeque::new(),
+        }
+    }
+
+    /// Accounts of the block producers expected to approve the block, in approvals order.
+    pub async fn resolve(&mut self, view: &BlockHeaderView) -> Result<Vec<AccountId>, String> {
+        let parent_epoch_id = match self.last_block.replace((view.hash, view.epoch_id)) {
+            Some((hash, epoch_id)) if hash == view.prev_hash => epoch_id,
+            _ => self.epoch_id(view.prev_hash).await?,
+        };
+
+        let mut approvers = Vec::clone(&*self.producers(parent_epoch_id, view.prev_hash).await?);
+        if view.approvals.len() > approvers.len() {
+            for account_id in self.producers(view.epoch_id, view.hash).await?.iter() {
+                if !approvers.contains(account_id) {
+                    approvers.push(account_id.clone());
+                }
+            }
+        }
+
+        if approvers.len() != view.approvals.len() {
+            return Err(format!(
+                "block has {} approvals but {} block producers",
+                view.approvals.len(),
+                approvers.len()
+            ));
+        }
+
+        Ok(approvers)
+    }
+
+    async fn epoch_id(&self, block_hash: CryptoHash) -> Result<CryptoHash, String> {
+        let block = self
+            .view_client
+            .send(GetBlock(BlockReference::BlockId(BlockId::Hash(block_hash))).with_span_context())
+            .await
+            .map_err(|err| err.to_string())?
+            .map_err(|err| err.to_string())?;
+
+        Ok(block.header.epoch_id)
+    }
+
+    /// Block producers of an epoch, fetched through one of its blocks on the first block
+    /// of the epoch and kept for the last [CACHED_EPOCHS] epochs.
+    async fn producers(
+        &mut self,
+        epoch_id: CryptoHash,
+        block_hash: CryptoHash,
+    ) -> Result<Arc<Vec<AccountId>>, String> {
+        if let Some((_, producers)) = self.producers.iter().find(|(id, _)| *id == epoch_id) {
+            return Ok(Arc::clone(producers));
+        }
+
+        let validators = self
+            .view_client
+            .send(
+                GetValidatorOrdered {
+                    block_id: Some(BlockId::Hash(block_hash)),
+                }
+                .with_span_context(),
+            )
+            .await
+            .map_err(|err| err.to_string())?
+            .map_err(|err| err.to_string())?;
+        let producers: Arc<Vec<AccountId>> = Arc::new(
+            validators
+                .into_iter()
+                .map(|v| v.take_account_id())
+                .collect(),
+        );
+
+        if self.producers.len() >= CACHED_EPOCHS {
+            self.producers.pop_front();
+        }
+        self.producers.push_back((epoch_id, Arc::clone(&producers)));
+
+        Ok(producers)
+    }
+}
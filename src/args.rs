@@ -0,0 +1,619 @@
+use crate::codec;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Nesting limit of borsh values and of base64 encoded JSON.
+const MAX_DEPTH: usize = 32;
+
+/// Limit of zero-sized elements in the borsh sequences and arrays of an args, whose
+/// count isn't bounded by the args length.
+const MAX_ZERO_SIZED_ELEMENTS: usize = 1 << 16;
+
+/// NEAR ABI file of a contract, given as `<account>=<path>`.
+#[derive(Clone, Debug)]
+pub(crate) struct AbiFile {
+    account_id: String,
+    path: PathBuf,
+}
+
+impl FromStr for AbiFile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((account_id, path)) if !account_id.is_empty() && !path.is_empty() => Ok(AbiFile {
+                account_id: account_id.to_string(),
+                path: PathBuf::from(path),
+            }),
+            _ => Err(format!("invalid ABI {:?}, expected <account>=<path>", s)),
+        }
+    }
+}
+
+/// Borsh schema of a function parameter, its declaration and the definitions of the
+/// types it refers to as found in the ABI.
+struct Param {
+    name: String,
+    declaration: String,
+    definitions: Map<String, Value>,
+}
+
+impl Param {
+    /// Length width of a sequence or tag width of an enum wider than the 16 bytes
+    /// integers are read into.
+    fn invalid_width(&self) -> Option<u64> {
+        self.definitions.values().find_map(|definition| {
+            let width = match (definition.get("Sequence"), definition.get("Enum")) {
+                (Some(sequence), _) => sequence.get("length_width"),
+                (_, Some(enumeration)) => enumeration.get("tag_width"),
+                _ => None,
+            };
+            width.and_then(Value::as_u64).filter(|width| *width > 16)
+        })
+    }
+}
+
+/// Decodes function call args to JSON.
+///
+/// Trimmed args are left undecoded. Args of functions whose contract ABI declares borsh
+/// serialized parameters are decoded with their schema. Other args are decoded when
+/// they are a JSON object or array, string values holding base64 encoded JSON objects
+/// or arrays, like the args of DAO proposals, being decoded in place.
+pub(crate) struct ArgsDecoder {
+    borsh_functions: HashMap<(String, String), Vec<Param>>,
+}
+
+impl ArgsDecoder {
+    pub fn new(abis: &[AbiFile]) -> Result<Self, String> {
+        let mut borsh_functions = HashMap::new();
+        for abi in abis {
+            let error = |err: &dyn std::fmt::Display| format!("{:?}: {}", abi.path, err);
+            let json = std::fs::read(&abi.path).map_err(|err| error(&err))?;
+            let root: Value = serde_json::from_slice(&json).map_err(|err| error(&err))?;
+
+            let functions = root["body"]["functions"]
+                .as_array()
+                .ok_or_else(|| format!("{:?}: no body.functions", abi.path))?;
+            for function in functions {
+                if function["params"]["serialization_type"] != "borsh" {
+                    continue;
+                }
+
+                let params: Vec<Param> = function["params"]["args"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|arg| Param {
+                        name: arg["name"].as_str().unwrap_or_default().to_string(),
+                        declaration: arg["type_schema"]["declaration"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                        definitions: arg["type_schema"]["definitions"]
+                            .as_object()
+                            .cloned()
+                            .unwrap_or_default(),
+                    })
+                    .collect();
+                let name = function["name"].as_str().unwrap_or_default().to_string();
+                if let Some(width) = params.iter().find_map(Param::invalid_width) {
+                    return Err(format!(
+                        "{:?}: function {} declares a {} bytes integer, at most 16 are supported",
+                        abi.path, name, width
+                    ));
+                }
+                borsh_functions.insert((abi.account_id.clone(), name), params);
+            }
+        }
+
+        Ok(ArgsDecoder { borsh_functions })
+    }
+
+    pub fn apply(&self, block: &mut codec::Block) {
+        for shard in block.shards.iter_mut() {
+            if let Some(chunk) = shard.chunk.as_mut() {
+                for tx in chunk.transactions.iter_mut() {
+                    if let Some(signed) = tx.transaction.as_mut() {
+                        self.decode_actions(&signed.receiver_id, &mut signed.actions);
+                    }
+                }
+                for receipt in chunk.receipts.iter_mut() {
+                    self.decode_receipt(receipt);
+                }
+            }
+
+            for outcome in shard.receipt_execution_outcomes.iter_mut() {
+                if let Some(receipt) = outcome.receipt.as_mut() {
+                    self.decode_receipt(receipt);
+                }
+            }
+        }
+    }
+
+    fn decode_receipt(&self, receipt: &mut codec::Receipt) {
+        if let Some(codec::receipt::Receipt::Action(action)) = receipt.receipt.as_mut() {
+            self.decode_actions(&receipt.receiver_id, &mut action.actions);
+        }
+    }
+
+    fn decode_actions(&self, receiver_id: &str, actions: &mut [codec::Action]) {
+        for action in actions {
+            match action.action.as_mut() {
+                Some(codec::action::Action::FunctionCall(call)) if call.args_trimmed.is_none() => {
+                    call.decoded_args = self.decode(receiver_id, &call.method_name, &call.args);
+                }
+                Some(codec::action::Action::Delegate(signed)) => {
+                    if let Some(delegate) = signed.delegate_action.as_mut() {
+                        self.decode_actions(&delegate.receiver_id, &mut delegate.actions);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn decode(
+        &self,
+        receiver_id: &str,
+        method_name: &str,
+        args: &[u8],
+    ) -> Option<codec::DecodedArgs> {
+        let key = (receiver_id.to_string(), method_name.to_string());
+        let (decoding, value) = match self.borsh_functions.get(&key) {
+            Some(params) => (codec::ArgsDecoding::BorshAbi, decode_borsh(params, args)?),
+            None => {
+                let mut value: Value = serde_json::from_slice(args).ok()?;
+                if !value.is_object() && !value.is_array() {
+                    return None;
+                }
+                match decode_base64_json(&mut value, 0) {
+                    true => (codec::ArgsDecoding::Base64Json, value),
+                    false => (codec::ArgsDecoding::Json, value),
+                }
+            }
+        };
+
+        Some(codec::DecodedArgs {
+            decoding: decoding as i32,
+            json: value.to_string(),
+        })
+    }
+}
+
+/// Replaces string values holding base64 encoded JSON objects or arrays with their
+/// decoded value, telling whether any was.
+fn decode_base64_json(value: &mut Value, depth: usize) -> bool {
+    if depth >= MAX_DEPTH {
+        return false;
+    }
+
+    match value {
+        Value::String(s) => {
+            let decoded = STANDARD
+                .decode(s.as_bytes())
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok());
+            match decoded {
+                Some(mut decoded) if decoded.is_object() || decoded.is_array() => {
+                    decode_base64_json(&mut decoded, depth + 1);
+                    *value = decoded;
+                    true
+                }
+                _ => false,
+            }
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .fold(false, |any, v| decode_base64_json(v, depth + 1) || any),
+        Value::Object(members) => members
+            .values_mut()
+            .fold(false, |any, v| decode_base64_json(v, depth + 1) || any),
+        _ => false,
+    }
+}
+
+/// Decodes borsh args as a JSON object of the function parameters, all bytes must be
+/// consumed.
+fn decode_borsh(params: &[Param], args: &[u8]) -> Option<Value> {
+    let mut reader = Reader {
+        bytes: args,
+        zero_sized_elements: MAX_ZERO_SIZED_ELEMENTS,
+    };
+    let mut object = Map::new();
+    for param in params {
+        let value = reader.value(&param.declaration, &param.definitions, 0)?;
+        object.insert(param.name.clone(), value);
+    }
+
+    match reader.bytes.is_empty() {
+        true => Some(Value::Object(object)),
+        false => None,
+    }
+}
+
+/// Reads borsh values described by borsh schema declarations and definitions, as
+/// serialized in NEAR ABI files by borsh 0.10 and 1.x.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    /// Zero-sized elements left to read.
+    zero_sized_elements: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Some(head)
+    }
+
+    fn uint(&mut self, width: usize) -> Option<u128> {
+        let mut le = [0u8; 16];
+        le.get_mut(..width)?.copy_from_slice(self.take(width)?);
+        Some(u128::from_le_bytes(le))
+    }
+
+    fn int(&mut self, width: usize) -> Option<i128> {
+        let unsigned = self.uint(width)?;
+        let shift = 128 - 8 * width as u32;
+        Some(((unsigned << shift) as i128) >> shift)
+    }
+
+    fn value(
+        &mut self,
+        declaration: &str,
+        definitions: &Map<String, Value>,
+        depth: usize,
+    ) -> Option<Value> {
+        if depth >= MAX_DEPTH {
+            return None;
+        }
+
+        let value = match declaration {
+            "u8" | "u16" | "u32" | "u64" => Value::from(self.uint(width(declaration))? as u64),
+            "i8" | "i16" | "i32" | "i64" => Value::from(self.int(width(declaration))? as i64),
+            // Wider than JSON numbers, rendered as decimal strings like NEAR amounts.
+            "u128" => Value::from(self.uint(16)?.to_string()),
+            "i128" => Value::from(self.int(16)?.to_string()),
+            "f32" => Value::from(f32::from_le_bytes(self.take(4)?.try_into().ok()?)),
+            "f64" => Value::from(f64::from_le_bytes(self.take(8)?.try_into().ok()?)),
+            "bool" => match self.take(1)?[0] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                _ => return None,
+            },
+            "string" | "String" => {
+                let len = self.uint(4)? as usize;
+                Value::from(std::str::from_utf8(self.take(len)?).ok()?)
+            }
+            "()" | "nil" => Value::Null,
+            _ => self.definition(definitions.get(declaration)?, definitions, depth)?,
+        };
+
+        Some(value)
+    }
+
+    fn definition(
+        &mut self,
+        definition: &Value,
+        definitions: &Map<String, Value>,
+        depth: usize,
+    ) -> Option<Value> {
+        let depth = depth + 1;
+
+        if let Some(array) = definition.get("Array") {
+            let len = array["length"].as_u64()? as usize;
+            return self.elements(array["elements"].as_str()?, len, definitions, depth);
+        }
+
+        if let Some(sequence) = definition.get("Sequence") {
+            // Borsh 0.10 sequences have a u32 length, 1.x ones declare its width, a zero
+            // width meaning a fixed length.
+            let len = match sequence.get("length_width").and_then(Value::as_u64) {
+                Some(0) => sequence["length_range"]["start"].as_u64()? as usize,
+                Some(width) => self.uint(width as usize)? as usize,
+                None => self.uint(4)? as usize,
+            };
+            return self.elements(sequence["elements"].as_str()?, len, definitions, depth);
+        }
+
+        if let Some(tuple) = definition.get("Tuple") {
+            let values = tuple["elements"]
+                .as_array()?
+                .iter()
+                .map(|element| self.value(element.as_str()?, definitions, depth))
+                .collect::<Option<Vec<_>>>()?;
+            return Some(Value::Array(values));
+        }
+
+        if let Some(enumeration) = definition.get("Enum") {
+            let width = enumeration
+                .get("tag_width")
+                .and_then(Value::as_u64)
+                .unwrap_or(1);
+            let tag = self.uint(width as usize)? as u64;
+            let variants = enumeration["variants"].as_array()?;
+            // Borsh 0.10 variants are `[name, declaration]` tagged by position, 1.x ones
+            // `[discriminant, name, declaration]`.
+            let (name, declaration) = variants.iter().enumerate().find_map(|(i, variant)| {
+                match variant.as_array()?.as_slice() {
+                    [name, declaration] if i as u64 == tag => Some((name, declaration)),
+                    [discriminant, name, declaration] if discriminant.as_u64() == Some(tag) => {
+                        Some((name, declaration))
+                    }
+                    _ => None,
+                }
+            })?;
+            let value = self.value(declaration.as_str()?, definitions, depth)?;
+
+            // Options are rendered as null or their value.
+            let names: Vec<&str> = variants
+                .iter()
+                .filter_map(|v| v.as_array()?.iter().rev().nth(1)?.as_str())
+                .collect();
+            return match (names.as_slice(), name.as_str()?) {
+                (["None", "Some"], "None") => Some(Value::Null),
+                (["None", "Some"], _) => Some(value),
+                (_, name) => {
+                    let mut object = Map::new();
+                    object.insert(name.to_string(), value);
+                    Some(Value::Object(object))
+                }
+            };
+        }
+
+        if let Some(structure) = definition.get("Struct") {
+            let fields = &structure["fields"];
+            if let Some(named) = fields.get("NamedFields") {
+                let mut object = Map::new();
+                for field in named.as_array()? {
+                    let [name, declaration] = field.as_array()?.as_slice() else {
+                        return None;
+                    };
+                    let value = self.value(declaration.as_str()?, definitions, depth)?;
+                    object.insert(name.as_str()?.to_string(), value);
+                }
+                return Some(Value::Object(object));
+            }
+            if let Some(unnamed) = fields.get("UnnamedFields") {
+                let values = unnamed
+                    .as_array()?
+                    .iter()
+                    .map(|declaration| self.value(declaration.as_str()?, definitions, depth))
+                    .collect::<Option<Vec<_>>>()?;
+                return Some(Value::Array(values));
+            }
+            return Some(Value::Null);
+        }
+
+        None
+    }
+
+    fn elements(
+        &mut self,
+        declaration: &str,
+        len: usize,
+        definitions: &Map<String, Value>,
+        depth: usize,
+    ) -> Option<Value> {
+        // Elements take at least a byte but zero-sized ones, such as `()` or empty
+        // structs, whose count is capped, which bounds allocations by the args length.
+        if len > self.bytes.len() + self.zero_sized_elements {
+            return None;
+        }
+
+        (0..len)
+            .map(|_| {
+                let remaining = self.bytes.len();
+                let value = self.value(declaration, definitions, depth)?;
+                if self.bytes.len() == remaining {
+                    self.zero_sized_elements = self.zero_sized_elements.checked_sub(1)?;
+                }
+                Some(value)
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array)
+    }
+}
+
+fn width(declaration: &str) -> usize {
+    declaration[1..].parse::<usize>().unwrap() / 8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn param(declaration: &str, definitions: Value) -> Param {
+        Param {
+            name: "value".to_string(),
+            declaration: declaration.to_string(),
+            definitions: definitions.as_object().cloned().unwrap(),
+        }
+    }
+
+    fn decoder(name: &str, functions: Value) -> Result<ArgsDecoder, String> {
+        let path = std::env::temp_dir().join(format!("abi-{}-{}.json", name, std::process::id()));
+        std::fs::write(&path, json!({"body": {"functions": functions}}).to_string()).unwrap();
+
+        let decoder = ArgsDecoder::new(&[AbiFile {
+            account_id: "contract.near".to_string(),
+            path: path.clone(),
+        }]);
+        std::fs::remove_file(path).unwrap();
+        decoder
+    }
+
+    fn decoded(decoded: Option<codec::DecodedArgs>) -> Option<(codec::ArgsDecoding, Value)> {
+        decoded.map(|args| {
+            (
+                codec::ArgsDecoding::from_i32(args.decoding).unwrap(),
+                serde_json::from_str(&args.json).unwrap(),
+            )
+        })
+    }
+
+    #[test]
+    fn decodes_json() {
+        let decoder = ArgsDecoder::new(&[]).unwrap();
+
+        let args = json!({"receiver_id": "bob.near", "memo": "café ☕"}).to_string();
+        assert_eq!(
+            decoded(decoder.decode("contract.near", "ft_transfer", args.as_bytes())),
+            Some((
+                codec::ArgsDecoding::Json,
+                json!({"receiver_id": "bob.near", "memo": "café ☕"})
+            ))
+        );
+
+        // Only objects and arrays are decoded.
+        assert_eq!(decoder.decode("contract.near", "f", b"\"text\""), None);
+        assert_eq!(decoder.decode("contract.near", "f", b"42"), None);
+        assert_eq!(decoder.decode("contract.near", "f", &[0xff, 0xfe]), None);
+    }
+
+    #[test]
+    fn decodes_base64_json() {
+        let decoder = ArgsDecoder::new(&[]).unwrap();
+        let inner = STANDARD.encode(json!({"amount": "10"}).to_string());
+        let args = json!({
+            "proposal": {"kind": {"FunctionCall": {"actions": [{"args": inner}]}}},
+            "description": "c2VudA==",
+        });
+
+        assert_eq!(
+            decoded(decoder.decode("dao.near", "add_proposal", args.to_string().as_bytes())),
+            Some((
+                codec::ArgsDecoding::Base64Json,
+                json!({
+                    "proposal": {
+                        "kind": {"FunctionCall": {"actions": [{"args": {"amount": "10"}}]}}
+                    },
+                    // Base64 that does not decode to a JSON object or array is kept.
+                    "description": "c2VudA==",
+                })
+            ))
+        );
+    }
+
+    #[test]
+    fn decodes_borsh_with_abi() {
+        let decoder = decoder(
+            "borsh",
+            json!([{
+                "name": "set",
+                "params": {
+                    "serialization_type": "borsh",
+                    "args": [
+                        {
+                            "name": "key",
+                            "type_schema": {"declaration": "String", "definitions": {}}
+                        },
+                        {
+                            "name": "value",
+                            "type_schema": {
+                                "declaration": "Option<u128>",
+                                "definitions": {
+                                    "Option<u128>": {"Enum": {"tag_width": 1, "variants": [
+                                        [0, "None", "()"],
+                                        [1, "Some", "u128"]
+                                    ]}}
+                                }
+                            }
+                        }
+                    ]
+                }
+            }]),
+        )
+        .unwrap();
+
+        let args = [&3u32.to_le_bytes()[..], b"key", &[1], &7u128.to_le_bytes()].concat();
+        assert_eq!(
+            decoded(decoder.decode("contract.near", "set", &args)),
+            Some((
+                codec::ArgsDecoding::BorshAbi,
+                json!({"key": "key", "value": "7"})
+            ))
+        );
+
+        let args = [&3u32.to_le_bytes()[..], b"key", &[0]].concat();
+        assert_eq!(
+            decoded(decoder.decode("contract.near", "set", &args)).map(|(_, value)| value),
+            Some(json!({"key": "key", "value": null}))
+        );
+
+        // Trailing bytes and truncated args are not decoded.
+        assert_eq!(
+            decoder.decode("contract.near", "set", &[&args[..], &[0]].concat()),
+            None
+        );
+        assert_eq!(decoder.decode("contract.near", "set", &args[..5]), None);
+    }
+
+    #[test]
+    fn rejects_abi_with_wide_integers() {
+        let functions = json!([{
+            "name": "set",
+            "params": {
+                "serialization_type": "borsh",
+                "args": [{
+                    "name": "values",
+                    "type_schema": {
+                        "declaration": "Vec<u8>",
+                        "definitions": {
+                            "Vec<u8>": {"Sequence": {"length_width": 17, "elements": "u8"}}
+                        }
+                    }
+                }]
+            }
+        }]);
+
+        assert!(decoder("wide", functions).is_err());
+    }
+
+    #[test]
+    fn uint_wider_than_u128_is_not_read() {
+        let mut reader = Reader {
+            bytes: &[0; 32],
+            zero_sized_elements: 0,
+        };
+        assert_eq!(reader.uint(17), None);
+    }
+
+    #[test]
+    fn decodes_sequences_of_empty_structs() {
+        let params = [param(
+            "Vec<Empty>",
+            json!({
+                "Vec<Empty>": {"Sequence": {"elements": "Empty"}},
+                "Empty": {"Struct": {"fields": "Empty"}},
+            }),
+        )];
+        let value = decode_borsh(&params, &3u32.to_le_bytes()).unwrap();
+        assert_eq!(value, json!({"value": [null, null, null]}));
+    }
+
+    #[test]
+    fn caps_zero_sized_elements() {
+        let params = [param(
+            "Vec<Vec<()>>",
+            json!({
+                "Vec<Vec<()>>": {"Sequence": {"elements": "Vec<()>"}},
+                "Vec<()>": {"Sequence": {"elements": "()"}},
+            }),
+        )];
+        let len = (MAX_ZERO_SIZED_ELEMENTS as u32 / 2).to_le_bytes();
+        let args = [&3u32.to_le_bytes()[..], &len, &len, &len].concat();
+        assert_eq!(decode_borsh(&params, &args), None);
+
+        let args = [&2u32.to_le_bytes()[..], &len, &len].concat();
+        assert!(decode_borsh(&params, &args).is_some());
+    }
+}
@@ -0,0 +1,456 @@
+use crate::codec;
+use crate::codec::state_change_cause::Cause;
+use crate::codec::state_change_value::Value;
+use futures::future::join_all;
+use near_client::{Query, QueryError, ViewClientActor};
+use near_indexer::near_primitives::hash::CryptoHash;
+use near_indexer::near_primitives::types::{AccountId, BlockId, BlockReference};
+use near_indexer::near_primitives::views::{QueryRequest, QueryResponseKind};
+use near_o11y::WithSpanContextExt;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Balance {
+    pub amount: u128,
+    pub locked: u128,
+    pub storage_usage: u64,
+}
+
+impl TryFrom<&codec::Account> for Balance {
+    type Error = String;
+
+    fn try_from(account: &codec::Account) -> Result<Self, Self::Error> {
+        let big_int = |value: &Option<codec::BigInt>| match value {
+            Some(value) => u128::try_from(value).map_err(|err| err.to_string()),
+            None => Ok(0),
+        };
+
+        Ok(Balance {
+            amount: big_int(&account.amount)?,
+            locked: big_int(&account.locked)?,
+            storage_usage: account.storage_usage,
+        })
+    }
+}
+
+/// Source of the balances accounts had before a block, the view client when indexing.
+pub(crate) trait BalanceLookup {
+    /// Balance of `account_id` after the block `block_hash`, zero for accounts unknown
+    /// there.
+    async fn balance(&self, account_id: &str, block_hash: CryptoHash) -> Result<Balance, String>;
+}
+
+impl BalanceLookup for actix::Addr<ViewClientActor> {
+    async fn balance(&self, account_id: &str, block_hash: CryptoHash) -> Result<Balance, String> {
+        let account_id = account_id
+            .parse::<AccountId>()
+            .map_err(|err| err.to_string())?;
+        let response = self
+            .send(
+                Query::new(
+                    BlockReference::BlockId(BlockId::Hash(block_hash)),
+                    QueryRequest::ViewAccount { account_id },
+                )
+                .with_span_context(),
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+
+        match response {
+            Ok(response) => match response.kind {
+                QueryResponseKind::ViewAccount(account) => Ok(Balance {
+                    amount: account.amount,
+                    locked: account.locked,
+                    storage_usage: account.storage_usage,
+                }),
+                _ => Err("unexpected view account query response".to_string()),
+            },
+            Err(QueryError::UnknownAccount { .. }) => Ok(Balance::default()),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
+
+/// Derives account balance changes from the account updates and deletions of block
+/// state changes.
+///
+/// The balance of an account before its first change in a block is its balance after
+/// the parent block when the account changed in it, and is otherwise looked up at the
+/// parent block, all accounts at once. Accounts unknown there are created with a zero
+/// balance. Changes of an account's code hash alone are not balance changes.
+pub(crate) struct BalanceTracker<L = actix::Addr<ViewClientActor>> {
+    lookup: L,
+    /// Hash of the last block balance changes were derived for, with the balances after
+    /// it of the accounts that changed in it.
+    last_block: Option<(Vec<u8>, HashMap<String, Balance>)>,
+}
+
+impl<L: BalanceLookup> BalanceTracker<L> {
+    pub fn new(lookup: L) -> Self {
+        BalanceTracker {
+            lookup,
+            last_block: None,
+        }
+    }
+
+    /// Balance changes of a full block whose parent is `prev_hash`.
+    pub async fn derive(
+        &mut self,
+        block: &codec::Block,
+        prev_hash: CryptoHash,
+    ) -> Result<Vec<codec::BalanceChange>, String> {
+        let last_block = self.last_block.take();
+        let mut known = match last_block {
+            Some((hash, balances)) if hash.as_slice() == prev_hash.as_ref() => balances,
+            _ => HashMap::new(),
+        };
+
+        let mut updates = vec![];
+        for change in &block.state_changes {
+            let value = change.value.as_ref().and_then(|v| v.value.as_ref());
+            let (account_id, after) = match value {
+                Some(Value::AccountUpdate(update)) => {
+                    let after = match &update.account {
+                        Some(account) => Balance::try_from(account)?,
+                        None => Balance::default(),
+                    };
+                    (update.account_id.as_str(), after)
+                }
+                Some(Value::AccountDeletion(deletion)) => {
+                    (deletion.account_id.as_str(), Balance::default())
+                }
+                _ => continue,
+            };
+            updates.push((change, account_id, after));
+        }
+
+        let unknown: HashSet<&str> = updates
+            .iter()
+            .map(|(_, account_id, _)| *account_id)
+            .filter(|account_id| !known.contains_key(*account_id))
+            .collect();
+        let queried = join_all(
+            unknown
+                .iter()
+                .map(|account_id| self.lookup.balance(account_id, prev_hash)),
+        )
+        .await;
+        for (account_id, balance) in unknown.into_iter().zip(queried) {
+            known.insert(account_id.to_string(), balance?);
+        }
+
+        let refunds: HashSet<&[u8]> = block
+            .shards
+            .iter()
+            .flat_map(|shard| shard.receipt_execution_outcomes.iter())
+            .filter_map(|outcome| outcome.receipt.as_ref())
+            .filter(|receipt| receipt.predecessor_id == "system")
+            .filter_map(|receipt| receipt.receipt_id.as_ref())
+            .map(|id| id.bytes.as_slice())
+            .collect();
+
+        let mut balances: HashMap<String, Balance> = HashMap::new();
+        let mut changes = vec![];
+        for (change, account_id, after) in updates {
+            let before = match balances.get(account_id) {
+                Some(balance) => *balance,
+                None => known[account_id],
+            };
+            balances.insert(account_id.to_string(), after);
+            if before == after {
+                continue;
+            }
+
+            let (cause, transaction_hash, receipt_id) = cause(change.cause.as_ref(), &refunds);
+            changes.push(codec::BalanceChange {
+                account_id: account_id.to_string(),
+                cause: cause as i32,
+                transaction_hash,
+                receipt_id,
+                amount_before: Some(codec::BigInt::from(before.amount)),
+                amount_after: Some(codec::BigInt::from(after.amount)),
+                locked_before: Some(codec::BigInt::from(before.locked)),
+                locked_after: Some(codec::BigInt::from(after.locked)),
+                storage_usage_before: before.storage_usage,
+                storage_usage_after: after.storage_usage,
+                shard_id: change.shard_id,
+            });
+        }
+
+        let hash = block.header.as_ref().and_then(|h| h.hash.as_ref());
+        self.last_block = hash.map(|hash| (hash.bytes.clone(), balances));
+
+        Ok(changes)
+    }
+}
+
+/// Maps a state change cause to a balance change cause and the hash of the transaction
+/// or receipt behind it, receipts from `system` being gas refunds.
+fn cause(
+    cause: Option<&codec::StateChangeCause>,
+    refunds: &HashSet<&[u8]>,
+) -> (
+    codec::BalanceChangeCause,
+    Option<codec::CryptoHash>,
+    Option<codec::CryptoHash>,
+) {
+    match cause.and_then(|c| c.cause.as_ref()) {
+        Some(Cause::TransactionProcessing(c)) => (
+            codec::BalanceChangeCause::Transaction,
+            c.tx_hash.clone(),
+            None,
+        ),
+        Some(Cause::ActionReceiptProcessingStarted(c)) => (
+            codec::BalanceChangeCause::Receipt,
+            None,
+            c.receipt_hash.clone(),
+        ),
+        Some(Cause::PostponedReceipt(c)) => {
+            (codec::BalanceChangeCause::Receipt, None, c.tx_hash.clone())
+        }
+        Some(Cause::ReceiptProcessing(c)) => {
+            let kind = match &c.tx_hash {
+                Some(id) if refunds.contains(id.bytes.as_slice()) => {
+                    codec::BalanceChangeCause::GasRefund
+                }
+                _ => codec::BalanceChangeCause::Receipt,
+            };
+            (kind, None, c.tx_hash.clone())
+        }
+        Some(Cause::ActionReceiptGasReward(c)) => (
+            codec::BalanceChangeCause::ContractReward,
+            None,
+            c.tx_hash.clone(),
+        ),
+        Some(Cause::ValidatorAccountsUpdate(_)) => {
+            (codec::BalanceChangeCause::ValidatorReward, None, None)
+        }
+        _ => (codec::BalanceChangeCause::Other, None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::state_change_cause::{
+        ActionReceiptGasReward, PostponedReceipt, ReceiptProcessing, TransactionProcessing,
+        ValidatorAccountsUpdate,
+    };
+    use codec::state_change_value::{AccountDeletion, AccountUpdate};
+    use std::cell::RefCell;
+
+    /// Balances after the parent block, recording the accounts looked up.
+    #[derive(Default)]
+    struct Lookup {
+        balances: HashMap<&'static str, Balance>,
+        queried: RefCell<Vec<String>>,
+    }
+
+    impl BalanceLookup for Lookup {
+        async fn balance(&self, account_id: &str, _: CryptoHash) -> Result<Balance, String> {
+            self.queried.borrow_mut().push(account_id.to_string());
+            Ok(self.balances.get(account_id).copied().unwrap_or_default())
+        }
+    }
+
+    fn balance(amount: u128) -> Balance {
+        Balance {
+            amount,
+            locked: 0,
+            storage_usage: 100,
+        }
+    }
+
+    fn hash(byte: u8) -> codec::CryptoHash {
+        codec::CryptoHash {
+            bytes: vec![byte; 32],
+        }
+    }
+
+    fn update(account_id: &str, amount: u128, cause: Cause) -> codec::StateChangeWithCause {
+        let account = codec::Account {
+            amount: Some(codec::BigInt::from(amount)),
+            locked: Some(codec::BigInt::from(0u128)),
+            storage_usage: 100,
+            ..Default::default()
+        };
+        change(
+            Value::AccountUpdate(AccountUpdate {
+                account_id: account_id.to_string(),
+                account: Some(account),
+            }),
+            cause,
+        )
+    }
+
+    fn change(value: Value, cause: Cause) -> codec::StateChangeWithCause {
+        codec::StateChangeWithCause {
+            value: Some(codec::StateChangeValue { value: Some(value) }),
+            cause: Some(codec::StateChangeCause { cause: Some(cause) }),
+            shard_id: 3,
+        }
+    }
+
+    fn block(hash_byte: u8, state_changes: Vec<codec::StateChangeWithCause>) -> codec::Block {
+        codec::Block {
+            header: Some(codec::BlockHeader {
+                hash: Some(hash(hash_byte)),
+                ..Default::default()
+            }),
+            state_changes,
+            ..Default::default()
+        }
+    }
+
+    fn derive(
+        tracker: &mut BalanceTracker<Lookup>,
+        block: &codec::Block,
+        prev_hash_byte: u8,
+    ) -> Vec<codec::BalanceChange> {
+        let prev_hash = CryptoHash([prev_hash_byte; 32]);
+        futures::executor::block_on(tracker.derive(block, prev_hash)).unwrap()
+    }
+
+    fn summary(changes: &[codec::BalanceChange]) -> Vec<(&str, codec::BalanceChangeCause, u128)> {
+        changes
+            .iter()
+            .map(|c| {
+                (
+                    c.account_id.as_str(),
+                    codec::BalanceChangeCause::from_i32(c.cause).unwrap(),
+                    u128::try_from(c.amount_after.as_ref().unwrap()).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn maps_state_change_causes() {
+        let mut lookup = Lookup::default();
+        lookup.balances.insert("alice.near", balance(100));
+        lookup.balances.insert("bob.near", balance(50));
+        let mut tracker = BalanceTracker::new(lookup);
+
+        let refund = codec::Receipt {
+            predecessor_id: "system".to_string(),
+            receipt_id: Some(hash(21)),
+            ..Default::default()
+        };
+        let mut block = block(
+            1,
+            vec![
+                update(
+                    "alice.near",
+                    90,
+                    Cause::TransactionProcessing(TransactionProcessing {
+                        tx_hash: Some(hash(20)),
+                    }),
+                ),
+                update(
+                    "alice.near",
+                    95,
+                    Cause::ReceiptProcessing(ReceiptProcessing {
+                        tx_hash: Some(hash(21)),
+                    }),
+                ),
+                update(
+                    "bob.near",
+                    60,
+                    Cause::PostponedReceipt(PostponedReceipt {
+                        tx_hash: Some(hash(22)),
+                    }),
+                ),
+                update(
+                    "bob.near",
+                    61,
+                    Cause::ActionReceiptGasReward(ActionReceiptGasReward {
+                        tx_hash: Some(hash(23)),
+                    }),
+                ),
+                update(
+                    "carol.near",
+                    7,
+                    Cause::ValidatorAccountsUpdate(ValidatorAccountsUpdate {}),
+                ),
+                update(
+                    "bob.near",
+                    61,
+                    Cause::ReceiptProcessing(ReceiptProcessing {
+                        tx_hash: Some(hash(24)),
+                    }),
+                ),
+            ],
+        );
+        block.shards = vec![codec::IndexerShard {
+            receipt_execution_outcomes: vec![codec::IndexerExecutionOutcomeWithReceipt {
+                receipt: Some(refund),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }];
+
+        let changes = derive(&mut tracker, &block, 0);
+        assert_eq!(
+            summary(&changes),
+            [
+                ("alice.near", codec::BalanceChangeCause::Transaction, 90),
+                ("alice.near", codec::BalanceChangeCause::GasRefund, 95),
+                ("bob.near", codec::BalanceChangeCause::Receipt, 60),
+                ("bob.near", codec::BalanceChangeCause::ContractReward, 61),
+                ("carol.near", codec::BalanceChangeCause::ValidatorReward, 7),
+            ]
+        );
+
+        assert_eq!(changes[0].transaction_hash, Some(hash(20)));
+        assert_eq!(changes[0].receipt_id, None);
+        assert_eq!(changes[1].receipt_id, Some(hash(21)));
+        assert_eq!(changes[2].transaction_hash, None);
+        assert_eq!(changes[2].receipt_id, Some(hash(22)));
+        assert_eq!(changes[2].amount_before, Some(codec::BigInt::from(50u128)));
+        assert_eq!(changes[4].amount_before, Some(codec::BigInt::from(0u128)));
+        assert!(changes.iter().all(|c| c.shard_id == 3));
+    }
+
+    #[test]
+    fn balances_of_the_parent_block_are_not_looked_up() {
+        let mut lookup = Lookup::default();
+        lookup.balances.insert("alice.near", balance(100));
+        let mut tracker = BalanceTracker::new(lookup);
+        let by_receipt = || Cause::ReceiptProcessing(ReceiptProcessing::default());
+
+        derive(
+            &mut tracker,
+            &block(1, vec![update("alice.near", 80, by_receipt())]),
+            0,
+        );
+        let child = block(2, vec![update("alice.near", 70, by_receipt())]);
+        let changes = derive(&mut tracker, &child, 1);
+        assert_eq!(changes[0].amount_before, Some(codec::BigInt::from(80u128)));
+        assert_eq!(*tracker.lookup.queried.borrow(), ["alice.near"]);
+
+        // A block on another fork looks balances up again.
+        let sibling = block(3, vec![update("alice.near", 60, by_receipt())]);
+        let changes = derive(&mut tracker, &sibling, 1);
+        assert_eq!(changes[0].amount_before, Some(codec::BigInt::from(100u128)));
+        assert_eq!(tracker.lookup.queried.borrow().len(), 2);
+    }
+
+    #[test]
+    fn deleted_accounts_end_with_a_zero_balance() {
+        let mut lookup = Lookup::default();
+        lookup.balances.insert("alice.near", balance(100));
+        let mut tracker = BalanceTracker::new(lookup);
+
+        let deletion = change(
+            Value::AccountDeletion(AccountDeletion {
+                account_id: "alice.near".to_string(),
+            }),
+            Cause::ReceiptProcessing(ReceiptProcessing::default()),
+        );
+        let changes = derive(&mut tracker, &block(1, vec![deletion]), 0);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].amount_before, Some(codec::BigInt::from(100u128)));
+        assert_eq!(changes[0].amount_after, Some(codec::BigInt::from(0u128)));
+        assert_eq!(changes[0].storage_usage_after, 0);
+    }
+}
@@ -0,0 +1,201 @@
+use super::BigInt;
+use std::fmt::{Display, Formatter};
+
+/// Number of yoctoNEAR in one NEAR.
+pub const YOCTO_PER_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+
+const NEAR_DECIMALS: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BigIntError {
+    /// The payload holds a value that does not fit in 128 bits.
+    Oversized(usize),
+    /// The payload is not the 16 bytes big-endian form produced by `From<u128>`.
+    NonCanonical(usize),
+    Overflow,
+    Underflow,
+    DivisionByZero,
+}
+
+impl Display for BigIntError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BigIntError::Oversized(len) => {
+                write!(
+                    f,
+                    "big int payload of {} bytes does not fit in 128 bits",
+                    len
+                )
+            }
+            BigIntError::NonCanonical(len) => {
+                write!(f, "big int payload of {} bytes is not canonical", len)
+            }
+            BigIntError::Overflow => write!(f, "big int arithmetic overflow"),
+            BigIntError::Underflow => write!(f, "big int arithmetic underflow"),
+            BigIntError::DivisionByZero => write!(f, "big int division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for BigIntError {}
+
+impl TryFrom<&BigInt> for u128 {
+    type Error = BigIntError;
+
+    /// Accepts the 16 bytes big-endian payload written by `From<u128>` as well as an
+    /// empty payload, the protobuf default, which decodes to zero.
+    fn try_from(i: &BigInt) -> Result<Self, Self::Error> {
+        let len = i.bytes.len();
+        match len {
+            0 => Ok(0),
+            16 => {
+                let mut be = [0u8; 16];
+                be.copy_from_slice(&i.bytes);
+                Ok(u128::from_be_bytes(be))
+            }
+            _ if len > 16 && i.bytes[..len - 16].iter().any(|b| *b != 0) => {
+                Err(BigIntError::Oversized(len))
+            }
+            _ => Err(BigIntError::NonCanonical(len)),
+        }
+    }
+}
+
+impl BigInt {
+    pub fn checked_add(&self, other: &BigInt) -> Result<BigInt, BigIntError> {
+        u128::try_from(self)?
+            .checked_add(u128::try_from(other)?)
+            .map(BigInt::from)
+            .ok_or(BigIntError::Overflow)
+    }
+
+    pub fn checked_sub(&self, other: &BigInt) -> Result<BigInt, BigIntError> {
+        u128::try_from(self)?
+            .checked_sub(u128::try_from(other)?)
+            .map(BigInt::from)
+            .ok_or(BigIntError::Underflow)
+    }
+
+    pub fn checked_mul(&self, factor: u128) -> Result<BigInt, BigIntError> {
+        u128::try_from(self)?
+            .checked_mul(factor)
+            .map(BigInt::from)
+            .ok_or(BigIntError::Overflow)
+    }
+
+    pub fn checked_div(&self, divisor: u128) -> Result<BigInt, BigIntError> {
+        u128::try_from(self)?
+            .checked_div(divisor)
+            .map(BigInt::from)
+            .ok_or(BigIntError::DivisionByZero)
+    }
+
+    /// Formats a yoctoNEAR amount in NEAR, keeping at most `max_decimals` decimals
+    /// (extra ones are truncated) and dropping trailing zeros, `1500000000000000000000000`
+    /// gives `1.5`.
+    pub fn to_near_string(&self, max_decimals: usize) -> Result<String, BigIntError> {
+        let yocto = u128::try_from(self)?;
+        let whole = yocto / YOCTO_PER_NEAR;
+        let fraction = format!("{:0>width$}", yocto % YOCTO_PER_NEAR, width = NEAR_DECIMALS);
+        let fraction = fraction[..max_decimals.min(NEAR_DECIMALS)].trim_end_matches('0');
+
+        Ok(match fraction {
+            "" => whole.to_string(),
+            _ => format!("{}.{}", whole, fraction),
+        })
+    }
+}
+
+/// Renders the decimal value, payloads that cannot be decoded are rendered as `0x`
+/// prefixed hex so that nothing gets lost.
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match u128::try_from(self) {
+            Ok(value) => write!(f, "{}", value),
+            Err(_) => write!(f, "0x{}", hex::encode(&self.bytes)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big_int(bytes: &[u8]) -> BigInt {
+        BigInt {
+            bytes: bytes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn decodes_canonical_payloads() {
+        assert_eq!(u128::try_from(&big_int(&[])), Ok(0));
+        assert_eq!(u128::try_from(&BigInt::from(u128::MAX)), Ok(u128::MAX));
+        assert_eq!(
+            u128::try_from(&BigInt::from(YOCTO_PER_NEAR)),
+            Ok(YOCTO_PER_NEAR)
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_payloads() {
+        let mut bytes = vec![1];
+        bytes.extend_from_slice(&[0; 16]);
+        assert_eq!(
+            u128::try_from(&big_int(&bytes)),
+            Err(BigIntError::Oversized(17))
+        );
+        assert_eq!(
+            big_int(&bytes).checked_add(&BigInt::from(1)),
+            Err(BigIntError::Oversized(17))
+        );
+    }
+
+    #[test]
+    fn rejects_non_canonical_payloads() {
+        assert_eq!(
+            u128::try_from(&big_int(&[1])),
+            Err(BigIntError::NonCanonical(1))
+        );
+        assert_eq!(
+            u128::try_from(&big_int(&[0; 20])),
+            Err(BigIntError::NonCanonical(20))
+        );
+    }
+
+    #[test]
+    fn displays_undecodable_payloads_as_hex() {
+        assert_eq!(BigInt::from(1500).to_string(), "1500");
+        assert_eq!(big_int(&[]).to_string(), "0");
+        assert_eq!(big_int(&[1, 2]).to_string(), "0x0102");
+        assert_eq!(
+            big_int(&[0; 17]).to_string(),
+            format!("0x{}", "00".repeat(17))
+        );
+    }
+
+    #[test]
+    fn json_round_trips_undecodable_payloads() {
+        for bytes in [vec![1, 2], vec![0; 17], vec![7; 20], BigInt::from(42).bytes] {
+            let json = serde_json::to_string(&big_int(&bytes)).unwrap();
+            assert_eq!(
+                serde_json::from_str::<BigInt>(&json).unwrap(),
+                big_int(&bytes)
+            );
+        }
+
+        assert!(serde_json::from_str::<BigInt>("\"0xzz\"").is_err());
+        assert!(serde_json::from_str::<BigInt>("\"-1\"").is_err());
+    }
+
+    #[test]
+    fn formats_near_amounts() {
+        let amount = BigInt::from(1_500_000_000_000_000_000_000_000);
+        assert_eq!(amount.to_near_string(24), Ok("1.5".to_string()));
+        assert_eq!(BigInt::from(1).to_near_string(2), Ok("0".to_string()));
+        assert_eq!(
+            big_int(&[1]).to_near_string(2),
+            Err(BigIntError::NonCanonical(1))
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use super::Block;
+use base64::Engine;
+use std::io::{self, Error, ErrorKind, Read};
+
+/// Largest decompressed block accepted, well above mainnet blocks, so that a corrupted
+/// payload cannot exhaust memory.
+const MAX_BLOCK_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Compresses encoded blocks with zstd, optionally with a trained dictionary. The
+/// compression context is kept between blocks.
+pub struct BlockCompressor {
+    compressor: zstd::bulk::Compressor<'static>,
+}
+
+impl BlockCompressor {
+    pub fn new(level: i32, dictionary: Option<&[u8]>) -> io::Result<Self> {
+        let compressor = match dictionary {
+            Some(dictionary) => zstd::bulk::Compressor::with_dictionary(level, dictionary)?,
+            None => zstd::bulk::Compressor::new(level)?,
+        };
+
+        Ok(BlockCompressor { compressor })
+    }
+
+    pub fn compress(&mut self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        self.compressor.compress(bytes)
+    }
+}
+
+/// Decodes the payload of a `FIRE BLOCK_Z` line, base64 encoded zstd compressed
+/// protobuf. The dictionary must be the one the block was compressed with.
+pub fn decode_compressed_block(payload: &str, dictionary: Option<&[u8]>) -> io::Result<Block> {
+    decode_with_limit(payload, dictionary, MAX_BLOCK_SIZE)
+}
+
+fn decode_with_limit(payload: &str, dictionary: Option<&[u8]>, limit: u64) -> io::Result<Block> {
+    let compressed = base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+    let decoder: Box<dyn Read> = match dictionary {
+        Some(dictionary) => Box::new(zstd::stream::read::Decoder::with_dictionary(
+            compressed.as_slice(),
+            dictionary,
+        )?),
+        None => Box::new(zstd::stream::read::Decoder::new(compressed.as_slice())?),
+    };
+
+    let mut bytes = Vec::new();
+    decoder.take(limit + 1).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > limit {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("decompressed block is larger than {} bytes", limit),
+        ));
+    }
+
+    prost::Message::decode(bytes.as_slice()).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::{BlockHeader, FunctionCallAction};
+    use prost::Message;
+
+    fn block() -> Block {
+        Block {
+            header: Some(BlockHeader {
+                height: 42,
+                ..Default::default()
+            }),
+            author: "test.near".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn payload(compressor: &mut BlockCompressor, block: &Block) -> String {
+        let compressed = compressor.compress(&block.encode_to_vec()).unwrap();
+        base64::engine::general_purpose::STANDARD.encode(compressed)
+    }
+
+    #[test]
+    fn blocks_round_trip() {
+        let payload = payload(&mut BlockCompressor::new(3, None).unwrap(), &block());
+        assert_eq!(decode_compressed_block(&payload, None).unwrap(), block());
+    }
+
+    #[test]
+    fn blocks_round_trip_with_dictionary() {
+        let args = FunctionCallAction {
+            method_name: "ft_transfer".to_string(),
+            args: br#"{"receiver_id":"bob.near","amount":"1"}"#.to_vec(),
+            ..Default::default()
+        };
+        let dictionary = [block().encode_to_vec(), args.encode_to_vec()].concat();
+
+        let mut compressor = BlockCompressor::new(3, Some(&dictionary)).unwrap();
+        let payload = payload(&mut compressor, &block());
+        let decoded = decode_compressed_block(&payload, Some(&dictionary)).unwrap();
+        assert_eq!(decoded, block());
+    }
+
+    #[test]
+    fn oversized_blocks_are_rejected() {
+        let block = Block {
+            author: "a".repeat(1024),
+            ..Default::default()
+        };
+        let payload = payload(&mut BlockCompressor::new(3, None).unwrap(), &block);
+
+        let err = decode_with_limit(&payload, None, 512).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(decode_with_limit(&payload, None, 2048).is_ok());
+    }
+}
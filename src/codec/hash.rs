@@ -0,0 +1,95 @@
+use super::{ConversionError, CryptoHash};
+use std::fmt::{Display, Formatter};
+
+/// Text encoding used when rendering a [CryptoHash].
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashEncoding {
+    Hex,
+    Base58,
+}
+
+/// Hex encoded hashes are 64 characters long while base58 encoded ones are 32 to 44.
+const HEX_LENGTH: usize = 64;
+
+impl CryptoHash {
+    pub fn from_hex(encoded: &str) -> Result<Self, ConversionError> {
+        let bytes =
+            hex::decode(encoded).map_err(|e| ConversionError::InvalidEncoding(e.to_string()))?;
+
+        Self::from_bytes(bytes)
+    }
+
+    pub fn from_base58(encoded: &str) -> Result<Self, ConversionError> {
+        let bytes = bs58::decode(encoded)
+            .into_vec()
+            .map_err(|e| ConversionError::InvalidEncoding(e.to_string()))?;
+
+        Self::from_bytes(bytes)
+    }
+
+    pub fn parse(encoded: &str, encoding: HashEncoding) -> Result<Self, ConversionError> {
+        match encoding {
+            HashEncoding::Hex => Self::from_hex(encoded),
+            HashEncoding::Base58 => Self::from_base58(encoded),
+        }
+    }
+
+    /// Parses a hex or base58 encoded hash, telling them apart by their length.
+    pub fn parse_any(encoded: &str) -> Result<Self, ConversionError> {
+        match encoded.len() {
+            HEX_LENGTH => Self::from_hex(encoded),
+            _ => Self::from_base58(encoded),
+        }
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.bytes)
+    }
+
+    pub fn to_base58(&self) -> String {
+        bs58::encode(&self.bytes).into_string()
+    }
+
+    pub fn encode(&self, encoding: HashEncoding) -> String {
+        match encoding {
+            HashEncoding::Hex => self.to_hex(),
+            HashEncoding::Base58 => self.to_base58(),
+        }
+    }
+
+    /// Displays the hash in `encoding`, where `Display for CryptoHash` always uses hex.
+    pub fn display(&self, encoding: HashEncoding) -> EncodedHash<'_> {
+        EncodedHash {
+            hash: self,
+            encoding,
+        }
+    }
+
+    /// Re-encodes a hash from one text encoding to another.
+    pub fn convert(
+        encoded: &str,
+        from: HashEncoding,
+        to: HashEncoding,
+    ) -> Result<String, ConversionError> {
+        Ok(Self::parse(encoded, from)?.encode(to))
+    }
+
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, ConversionError> {
+        if bytes.len() != 32 {
+            return Err(ConversionError::InvalidHash(bytes.len()));
+        }
+
+        Ok(CryptoHash { bytes })
+    }
+}
+
+pub struct EncodedHash<'a> {
+    hash: &'a CryptoHash,
+    encoding: HashEncoding,
+}
+
+impl Display for EncodedHash<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.hash.encode(self.encoding))
+    }
+}
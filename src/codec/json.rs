@@ -0,0 +1,197 @@
+//! Canonical JSON representation of the `sf.near.type.v1` model.
+//!
+//! Field names are the lower camel case form of the protobuf field names, oneofs are
+//! flattened in their parent message and raw bytes are base64 encoded, like pbjson
+//! does. `CryptoHash` renders as base58, the encoding NEAR explorers use, unless another
+//! encoding is picked through [with_hash_encoding], and parses from hex or base58.
+//! `BigInt` renders as a decimal string, or `0x` prefixed hex when its payload is not a
+//! valid 128 bits value. Enumerations render by name, values unknown to this version of
+//! the model render as their number. Missing fields parse as their protobuf default.
+
+use super::{BigInt, ConversionError, CryptoHash, HashEncoding};
+use std::cell::Cell;
+
+thread_local! {
+    static HASH_ENCODING: Cell<HashEncoding> = const { Cell::new(HashEncoding::Base58) };
+}
+
+/// Runs `f` with hashes serialized in `encoding` on the current thread, restoring the
+/// previous encoding afterwards.
+///
+/// This is the one place the hash encoding is not passed explicitly: serde derives give
+/// no way to hand state down to field serializers, so the JSON encoding is a thread
+/// local scoped to `f`. `FIRE` lines, block logs and per shard outputs take theirs from
+/// their writer.
+pub fn with_hash_encoding<T>(encoding: HashEncoding, f: impl FnOnce() -> T) -> T {
+    let previous = HASH_ENCODING.with(|cell| cell.replace(encoding));
+    let result = f();
+    HASH_ENCODING.with(|cell| cell.set(previous));
+    result
+}
+
+pub(crate) mod bytes {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+pub(crate) mod optional_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        bytes: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => super::bytes::serialize(bytes, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        #[derive(Deserialize)]
+        struct Bytes(#[serde(with = "super::bytes")] Vec<u8>);
+
+        Ok(Option::<Bytes>::deserialize(deserializer)?.map(|bytes| bytes.0))
+    }
+}
+
+pub(crate) mod repeated_bytes {
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(items: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(serde::Serialize)]
+        struct Bytes<'a>(#[serde(with = "super::bytes")] &'a [u8]);
+
+        let mut seq = serializer.serialize_seq(Some(items.len()))?;
+        for item in items {
+            seq.serialize_element(&Bytes(item))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        #[derive(Deserialize)]
+        struct Bytes(#[serde(with = "super::bytes")] Vec<u8>);
+
+        Ok(Vec::<Bytes>::deserialize(deserializer)?
+            .into_iter()
+            .map(|bytes| bytes.0)
+            .collect())
+    }
+}
+
+/// Protobuf enumerations, found back from the `i32` prost stores in messages.
+pub(crate) trait Enumeration:
+    Copy + Into<i32> + serde::Serialize + serde::de::DeserializeOwned
+{
+    fn from_i32(value: i32) -> Option<Self>;
+}
+
+macro_rules! enumerations {
+    ($($name:ident),* $(,)?) => {$(
+        impl Enumeration for super::$name {
+            fn from_i32(value: i32) -> Option<Self> {
+                super::$name::from_i32(value)
+            }
+        }
+    )*};
+}
+
+enumerations!(
+    ArgsDecoding,
+    BalanceChangeCause,
+    CurveKind,
+    Direction,
+    ExecutionMetadata,
+    FtTransferKind,
+    FunctionCallErrorSer,
+    InvalidTxError,
+    NftActivityKind,
+    ReceiptStatus,
+    ReceiptValidationError,
+);
+
+pub(crate) mod enumeration {
+    use super::Enumeration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<E: Enumeration, S: Serializer>(
+        value: &i32,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match E::from_i32(*value) {
+            Some(known) => known.serialize(serializer),
+            None => serializer.serialize_i32(*value),
+        }
+    }
+
+    pub fn deserialize<'de, E: Enumeration, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<i32, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Value<E> {
+            Name(E),
+            Number(i32),
+        }
+
+        Ok(match Value::<E>::deserialize(deserializer)? {
+            Value::Name(known) => known.into(),
+            Value::Number(value) => value,
+        })
+    }
+}
+
+impl From<CryptoHash> for String {
+    fn from(h: CryptoHash) -> Self {
+        h.encode(HASH_ENCODING.with(Cell::get))
+    }
+}
+
+impl TryFrom<String> for CryptoHash {
+    type Error = ConversionError;
+
+    fn try_from(encoded: String) -> Result<Self, Self::Error> {
+        CryptoHash::parse_any(&encoded)
+    }
+}
+
+impl From<BigInt> for String {
+    fn from(i: BigInt) -> Self {
+        i.to_string()
+    }
+}
+
+impl TryFrom<String> for BigInt {
+    type Error = ConversionError;
+
+    /// Parses the decimal value, or the `0x` prefixed hex payload `Display` renders for
+    /// payloads that are not a valid 128 bits value, kept as is.
+    fn try_from(encoded: String) -> Result<Self, Self::Error> {
+        let error = |e: &dyn std::fmt::Display| ConversionError::InvalidEncoding(e.to_string());
+        match encoded.strip_prefix("0x") {
+            Some(payload) => hex::decode(payload)
+                .map(|bytes| BigInt { bytes })
+                .map_err(|e| error(&e)),
+            None => encoded
+                .parse::<u128>()
+                .map(BigInt::from)
+                .map_err(|e| error(&e)),
+        }
+    }
+}
@@ -1,7 +1,16 @@
+mod bigint;
 #[path = "sf.near.r#type.v1.rs"]
 mod codec;
+mod compression;
+mod hash;
+mod json;
+mod views;
 
+pub use bigint::{BigIntError, YOCTO_PER_NEAR};
 pub use codec::*;
+pub use compression::{decode_compressed_block, BlockCompressor};
+pub use hash::HashEncoding;
+pub use json::with_hash_encoding;
 use near_crypto::PublicKey as NearPublicKey;
 use near_crypto::Signature as NearSignature;
 use near_indexer::near_primitives;
@@ -12,10 +21,46 @@ use near_indexer::near_primitives::views::{
     DataReceiverView, ExecutionMetadataView, ExecutionStatusView, ReceiptEnumView,
 };
 use near_indexer::StreamerMessage;
+pub use views::ConversionError;
 
-use hex;
 use std::fmt::{Display, Formatter};
 
+impl From<near_indexer::StreamerMessage> for Block {
+    /// Moves the message content into the block instead of cloning it, prefer it over
+    /// the borrowing conversion when the message is not needed afterwards.
+    fn from(mut sm: StreamerMessage) -> Self {
+        let state_changes = sm
+            .shards
+            .iter_mut()
+            .flat_map(|s| {
+                let shard_id = s.shard_id;
+                std::mem::take(&mut s.state_changes)
+                    .into_iter()
+                    .map(move |change| StateChangeWithCause {
+                        shard_id,
+                        ..StateChangeWithCause::from(change)
+                    })
+            })
+            .collect();
+
+        Block {
+            header: Some(BlockHeader::from(sm.block.header)),
+            shards: sm.shards.into_iter().map(IndexerShard::from).collect(),
+            author: sm.block.author.to_string(),
+            chunk_headers: sm.block.chunks.into_iter().map(ChunkHeader::from).collect(),
+            state_changes,
+            filtering_applied: false,
+            filter_hash: vec![],
+            events: vec![],
+            ft_transfers: vec![],
+            nft_activities: vec![],
+            finalized_transactions: vec![],
+            balance_changes: vec![],
+            balance_changes_error: String::new(),
+        }
+    }
+}
+
 impl From<&near_indexer::StreamerMessage> for Block {
     fn from(sm: &StreamerMessage) -> Self {
         Block {
@@ -28,15 +73,35 @@ impl From<&near_indexer::StreamerMessage> for Block {
                 .iter()
                 .map(|ch| ChunkHeader::from(ch))
                 .collect(),
-            state_changes: vec![],
+            state_changes: sm
+                .shards
+                .iter()
+                .flat_map(|s| {
+                    s.state_changes.iter().map(|change| StateChangeWithCause {
+                        shard_id: s.shard_id,
+                        ..StateChangeWithCause::from(change.clone())
+                    })
+                })
+                .collect(),
+            filtering_applied: false,
+            filter_hash: vec![],
+            events: vec![],
+            ft_transfers: vec![],
+            nft_activities: vec![],
+            finalized_transactions: vec![],
+            balance_changes: vec![],
+            balance_changes_error: String::new(),
         }
     }
 }
 
 impl From<&near_views::BlockHeaderView> for BlockHeader {
     fn from(h: &near_views::BlockHeaderView) -> Self {
-        let challenges_result = &h.challenges_result;
-        let validator_proposals = &h.validator_proposals;
+        let approvals: Vec<Option<Signature>> = h
+            .approvals
+            .iter()
+            .map(|s| s.as_deref().map(Signature::from))
+            .collect();
 
         BlockHeader {
             hash: Some(CryptoHash::from(h.hash)),
@@ -58,17 +123,19 @@ impl From<&near_views::BlockHeaderView> for BlockHeader {
             challenges_root: Some(CryptoHash::from(h.challenges_root)),
             timestamp: h.timestamp,
             random_value: Some(CryptoHash::from(h.random_value)),
-            validator_proposals: validator_proposals
-                .into_iter()
-                .map(|p| ValidatorStake::from(p))
+            validator_proposals: h
+                .validator_proposals
+                .iter()
+                .map(ValidatorStake::from)
                 .collect(),
             chunk_mask: h.chunk_mask.clone(),
             gas_price: Some(BigInt::from(h.gas_price)),
-            block_ordinal: 0, //todo: this is v3 feature, what that means?
+            block_ordinal: h.block_ordinal.unwrap_or(0),
             total_supply: Some(BigInt::from(h.total_supply)),
-            challenges_result: challenges_result
-                .into_iter()
-                .map(|cr| SlashedValidator::from(cr))
+            challenges_result: h
+                .challenges_result
+                .iter()
+                .map(SlashedValidator::from)
                 .collect(),
             last_final_block_height: 0,
             last_final_block: Some(CryptoHash::from(h.last_final_block)),
@@ -76,18 +143,105 @@ impl From<&near_views::BlockHeaderView> for BlockHeader {
             last_ds_final_block: Some(CryptoHash::from(h.last_ds_final_block)),
             next_bp_hash: Some(CryptoHash::from(h.next_bp_hash)),
             block_merkle_root: Some(CryptoHash::from(h.block_merkle_root)),
-            epoch_sync_data_hash: vec![], //todo: this is v3 feature, what that means?
-            approvals: h
-                .clone()
-                .approvals
+            epoch_sync_data_hash: h.epoch_sync_data_hash.map(Vec::from).unwrap_or_default(),
+            approvals: approvals.iter().flatten().cloned().collect(),
+            signature: Some(Signature::from(&h.signature)),
+            latest_protocol_version: h.latest_protocol_version,
+            block_approvals: approvals
                 .into_iter()
-                .filter_map(|s| match s {
-                    None => None,
-                    Some(sig) => Some(sig.into()),
+                .map(|signature| BlockApproval {
+                    account_id: String::new(),
+                    signature,
                 })
                 .collect(),
-            signature: Some(h.signature.clone().into()),
+            block_body_hash: h.block_body_hash.map(CryptoHash::from),
+            chunk_endorsements: h
+                .chunk_endorsements
+                .as_ref()
+                .map(|bitmaps| ChunkEndorsements {
+                    bitmaps: bitmaps.clone(),
+                }),
+            block_approvals_error: String::new(),
+        }
+    }
+}
+
+impl From<near_views::BlockHeaderView> for BlockHeader {
+    /// Moves the approvals, proposals and other variable length fields out of the view.
+    fn from(h: near_views::BlockHeaderView) -> Self {
+        let approvals: Vec<Option<Signature>> = h
+            .approvals
+            .into_iter()
+            .map(|s| s.map(Signature::from))
+            .collect();
+
+        BlockHeader {
+            hash: Some(CryptoHash::from(h.hash)),
+            height: h.height,
+            prev_hash: Some(CryptoHash::from(h.prev_hash)),
+            timestamp_nanosec: h.timestamp_nanosec,
+            prev_height: h.prev_height.unwrap_or(0),
+            epoch_id: Some(CryptoHash::from(h.epoch_id)),
+            next_epoch_id: Some(CryptoHash::from(h.next_epoch_id)),
+            prev_state_root: Some(CryptoHash::from(h.prev_state_root)),
+            chunk_receipts_root: Some(CryptoHash::from(h.chunk_receipts_root)),
+            chunk_headers_root: Some(CryptoHash::from(h.chunk_headers_root)),
+            chunk_tx_root: Some(CryptoHash::from(h.chunk_tx_root)),
+            outcome_root: Some(CryptoHash::from(h.outcome_root)),
+            chunks_included: h.chunks_included,
+            challenges_root: Some(CryptoHash::from(h.challenges_root)),
+            timestamp: h.timestamp,
+            random_value: Some(CryptoHash::from(h.random_value)),
+            validator_proposals: h
+                .validator_proposals
+                .into_iter()
+                .map(ValidatorStake::from)
+                .collect(),
+            chunk_mask: h.chunk_mask,
+            gas_price: Some(BigInt::from(h.gas_price)),
+            block_ordinal: h.block_ordinal.unwrap_or(0),
+            total_supply: Some(BigInt::from(h.total_supply)),
+            challenges_result: h
+                .challenges_result
+                .into_iter()
+                .map(SlashedValidator::from)
+                .collect(),
+            last_final_block_height: 0,
+            last_final_block: Some(CryptoHash::from(h.last_final_block)),
+            last_ds_final_block_height: 0,
+            last_ds_final_block: Some(CryptoHash::from(h.last_ds_final_block)),
+            next_bp_hash: Some(CryptoHash::from(h.next_bp_hash)),
+            block_merkle_root: Some(CryptoHash::from(h.block_merkle_root)),
+            epoch_sync_data_hash: h.epoch_sync_data_hash.map(Vec::from).unwrap_or_default(),
+            approvals: approvals.iter().flatten().cloned().collect(),
+            signature: Some(Signature::from(h.signature)),
             latest_protocol_version: h.latest_protocol_version,
+            block_approvals: approvals
+                .into_iter()
+                .map(|signature| BlockApproval {
+                    account_id: String::new(),
+                    signature,
+                })
+                .collect(),
+            block_body_hash: h.block_body_hash.map(CryptoHash::from),
+            chunk_endorsements: h
+                .chunk_endorsements
+                .map(|bitmaps| ChunkEndorsements { bitmaps }),
+            block_approvals_error: String::new(),
+        }
+    }
+}
+
+impl From<near_indexer::IndexerShard> for IndexerShard {
+    fn from(is: near_indexer::IndexerShard) -> Self {
+        IndexerShard {
+            shard_id: is.shard_id,
+            chunk: is.chunk.map(IndexerChunk::from),
+            receipt_execution_outcomes: is
+                .receipt_execution_outcomes
+                .into_iter()
+                .map(IndexerExecutionOutcomeWithReceipt::from)
+                .collect(),
         }
     }
 }
@@ -111,6 +265,149 @@ impl From<&near_indexer::IndexerShard> for IndexerShard {
     }
 }
 
+impl From<near_views::StateChangeWithCauseView> for StateChangeWithCause {
+    fn from(change: near_views::StateChangeWithCauseView) -> Self {
+        StateChangeWithCause {
+            value: Some(StateChangeValue::from(change.value)),
+            cause: Some(StateChangeCause::from(change.cause)),
+            shard_id: 0,
+        }
+    }
+}
+
+impl From<near_views::StateChangeCauseView> for StateChangeCause {
+    fn from(cause: near_views::StateChangeCauseView) -> Self {
+        use near_views::StateChangeCauseView as View;
+        use state_change_cause::*;
+
+        let cause = match cause {
+            View::NotWritableToDisk => Cause::NotWritableToDisk(NotWritableToDisk {}),
+            View::InitialState => Cause::InitialState(InitialState {}),
+            View::TransactionProcessing { tx_hash } => {
+                Cause::TransactionProcessing(TransactionProcessing {
+                    tx_hash: Some(CryptoHash::from(tx_hash)),
+                })
+            }
+            View::ActionReceiptProcessingStarted { receipt_hash } => {
+                Cause::ActionReceiptProcessingStarted(ActionReceiptProcessingStarted {
+                    receipt_hash: Some(CryptoHash::from(receipt_hash)),
+                })
+            }
+            // The schema names the receipt hash of the following causes `tx_hash`.
+            View::ActionReceiptGasReward { receipt_hash } => {
+                Cause::ActionReceiptGasReward(ActionReceiptGasReward {
+                    tx_hash: Some(CryptoHash::from(receipt_hash)),
+                })
+            }
+            View::ReceiptProcessing { receipt_hash } => {
+                Cause::ReceiptProcessing(ReceiptProcessing {
+                    tx_hash: Some(CryptoHash::from(receipt_hash)),
+                })
+            }
+            View::PostponedReceipt { receipt_hash } => Cause::PostponedReceipt(PostponedReceipt {
+                tx_hash: Some(CryptoHash::from(receipt_hash)),
+            }),
+            View::UpdatedDelayedReceipts => {
+                Cause::UpdatedDelayedReceipts(UpdatedDelayedReceipts {})
+            }
+            View::ValidatorAccountsUpdate => {
+                Cause::ValidatorAccountsUpdate(ValidatorAccountsUpdate {})
+            }
+            View::Migration => Cause::Migration(Migration {}),
+            // Resharding has no counterpart in the schema.
+            View::ReshardingV2 => return StateChangeCause { cause: None },
+        };
+
+        StateChangeCause { cause: Some(cause) }
+    }
+}
+
+impl From<near_views::StateChangeValueView> for StateChangeValue {
+    fn from(value: near_views::StateChangeValueView) -> Self {
+        use near_views::StateChangeValueView as View;
+        use state_change_value::*;
+
+        let value = match value {
+            View::AccountUpdate {
+                account_id,
+                account,
+            } => Value::AccountUpdate(AccountUpdate {
+                account_id: account_id.to_string(),
+                account: Some(Account::from(account)),
+            }),
+            View::AccountDeletion { account_id } => Value::AccountDeletion(AccountDeletion {
+                account_id: account_id.to_string(),
+            }),
+            View::AccessKeyUpdate {
+                account_id,
+                public_key,
+                access_key,
+            } => Value::AccessKeyUpdate(AccessKeyUpdate {
+                account_id: account_id.to_string(),
+                public_key: Some(PublicKey::from(public_key)),
+                access_key: Some(AccessKey::from(access_key)),
+            }),
+            View::AccessKeyDeletion {
+                account_id,
+                public_key,
+            } => Value::AccessKeyDeletion(AccessKeyDeletion {
+                account_id: account_id.to_string(),
+                public_key: Some(PublicKey::from(public_key)),
+            }),
+            View::DataUpdate {
+                account_id,
+                key,
+                value,
+            } => Value::DataUpdate(DataUpdate {
+                account_id: account_id.to_string(),
+                key: Vec::from(key),
+                value: Vec::from(value),
+            }),
+            View::DataDeletion { account_id, key } => Value::DataDeletion(DataDeletion {
+                account_id: account_id.to_string(),
+                key: Vec::from(key),
+            }),
+            View::ContractCodeUpdate { account_id, code } => {
+                Value::ContractCodeUpdate(ContractCodeUpdate {
+                    account_id: account_id.to_string(),
+                    code,
+                    code_hash: vec![],
+                    code_len: 0,
+                })
+            }
+            View::ContractCodeDeletion { account_id } => {
+                Value::ContractDeletion(ContractCodeDeletion {
+                    account_id: account_id.to_string(),
+                })
+            }
+        };
+
+        StateChangeValue { value: Some(value) }
+    }
+}
+
+impl From<near_views::AccountView> for Account {
+    fn from(account: near_views::AccountView) -> Self {
+        Account {
+            amount: Some(BigInt::from(account.amount)),
+            locked: Some(BigInt::from(account.locked)),
+            code_hash: Some(CryptoHash::from(account.code_hash)),
+            storage_usage: account.storage_usage,
+        }
+    }
+}
+
+impl From<near_indexer::IndexerExecutionOutcomeWithReceipt> for IndexerExecutionOutcomeWithReceipt {
+    fn from(r: near_indexer::IndexerExecutionOutcomeWithReceipt) -> Self {
+        IndexerExecutionOutcomeWithReceipt {
+            execution_outcome: Some(ExecutionOutcomeWithId::from(r.execution_outcome)),
+            receipt: Some(Receipt::from(r.receipt)),
+            transaction_hash: None,
+            receipt_depth: 0,
+        }
+    }
+}
+
 impl From<&near_indexer::IndexerExecutionOutcomeWithReceipt>
     for IndexerExecutionOutcomeWithReceipt
 {
@@ -118,6 +415,8 @@ impl From<&near_indexer::IndexerExecutionOutcomeWithReceipt>
         IndexerExecutionOutcomeWithReceipt {
             execution_outcome: Some(ExecutionOutcomeWithId::from(r.execution_outcome.clone())),
             receipt: Some(Receipt::from(r.receipt.clone())),
+            transaction_hash: None,
+            receipt_depth: 0,
         }
     }
 }
@@ -136,7 +435,7 @@ impl From<near_views::ReceiptView> for Receipt {
                     output_data_receivers,
                     input_data_ids,
                     actions,
-                    ..
+                    is_promise_yield,
                 } => Some(receipt::Receipt::Action {
                     0: ReceiptAction {
                         signer_id: signer_id.to_string(),
@@ -151,15 +450,23 @@ impl From<near_views::ReceiptView> for Receipt {
                             .map(|i| CryptoHash::from(i))
                             .collect(),
                         actions: actions.into_iter().map(|a| Action::from(a)).collect(),
+                        is_promise_yield,
                     },
                 }),
-                ReceiptEnumView::Data { data_id, data, .. } => Some(receipt::Receipt::Data {
+                ReceiptEnumView::Data {
+                    data_id,
+                    data,
+                    is_promise_resume,
+                } => Some(receipt::Receipt::Data {
                     0: ReceiptData {
                         data_id: Some(CryptoHash::from(data_id)),
-                        data: data.unwrap_or(vec![]),
+                        data,
+                        data_trimmed: None,
+                        is_promise_resume,
                     },
                 }),
             },
+            priority: r.priority,
         }
     }
 }
@@ -173,6 +480,21 @@ impl From<near_views::DataReceiverView> for DataReceiver {
     }
 }
 
+impl From<near_indexer::IndexerChunkView> for IndexerChunk {
+    fn from(s: near_indexer::IndexerChunkView) -> Self {
+        IndexerChunk {
+            author: s.author.to_string(),
+            header: Some(ChunkHeader::from(s.header)),
+            transactions: s
+                .transactions
+                .into_iter()
+                .map(IndexerTransactionWithOutcome::from)
+                .collect(),
+            receipts: s.receipts.into_iter().map(Receipt::from).collect(),
+        }
+    }
+}
+
 impl From<&near_indexer::IndexerChunkView> for IndexerChunk {
     fn from(s: &near_indexer::IndexerChunkView) -> Self {
         IndexerChunk {
@@ -228,6 +550,18 @@ impl From<near_views::ExecutionOutcomeWithIdView> for ExecutionOutcomeWithId {
 
 impl From<near_views::ExecutionOutcomeView> for ExecutionOutcome {
     fn from(o: near_views::ExecutionOutcomeView) -> Self {
+        let gas_profile = o
+            .metadata
+            .gas_profile
+            .unwrap_or_default()
+            .into_iter()
+            .map(|cost| CostGasUsed {
+                cost_category: cost.cost_category,
+                cost: cost.cost,
+                gas_used: cost.gas_used,
+            })
+            .collect();
+
         ExecutionOutcome {
             logs: o.logs,
             receipt_ids: o
@@ -239,9 +573,13 @@ impl From<near_views::ExecutionOutcomeView> for ExecutionOutcome {
             tokens_burnt: Some(BigInt::from(o.tokens_burnt)),
             executor_id: o.executor_id.to_string(),
             status: Some(execution_outcome::Status::from(o.status)),
-            metadata: match o.metadata {
-                ExecutionMetadataView { .. } => ExecutionMetadata::V1.into(),
+            metadata: match o.metadata.version {
+                1 => ExecutionMetadata::V1.into(),
+                2 => ExecutionMetadata::V2.into(),
+                _ => ExecutionMetadata::V3.into(),
             },
+            trimmed_logs: vec![],
+            gas_profile,
         }
     }
 }
@@ -253,7 +591,10 @@ impl From<near_views::ExecutionStatusView> for execution_outcome::Status {
                 0: UnknownExecutionStatus {},
             },
             ExecutionStatusView::SuccessValue(v) => execution_outcome::Status::SuccessValue {
-                0: SuccessValueExecutionStatus { value: v.into() },
+                0: SuccessValueExecutionStatus {
+                    value: v.into(),
+                    value_trimmed: None,
+                },
             },
             ExecutionStatusView::SuccessReceiptId(v) => {
                 execution_outcome::Status::SuccessReceiptId {
@@ -265,11 +606,15 @@ impl From<near_views::ExecutionStatusView> for execution_outcome::Status {
 
             ExecutionStatusView::Failure(tx_err) => execution_outcome::Status::Failure {
                 0: FailureExecutionStatus {
+                    invalid_tx_error_detail: match &tx_err {
+                        near_errors::TxExecutionError::InvalidTxError(e) => error_detail(e),
+                        near_errors::TxExecutionError::ActionError(_) => String::new(),
+                    },
                     failure: match tx_err {
                         near_errors::TxExecutionError::ActionError(ae) => {
                             Some(failure_execution_status::Failure::ActionError {
                                 0: ActionError {
-                                    index: ae.index.unwrap_or(0),
+                                    index: ae.index,
                                     kind: Some(match ae.kind {
                                         ActionErrorKind::AccountAlreadyExists { account_id } => {
                                             action_error::Kind::AccountAlreadyExist {
@@ -382,7 +727,7 @@ impl From<near_views::ExecutionStatusView> for execution_outcome::Status {
                                         },
                                         ActionErrorKind::FunctionCallError(fce) => {
                                             action_error::Kind::FunctionCall {
-                                                0: FunctionCallErrorKind { error: match fce {
+                                                0: FunctionCallErrorKind { detail: error_detail(&fce), error: match fce {
                                                     near_errors::FunctionCallError::CompilationError(_) => {
                                                         FunctionCallErrorSer::CompilationError.into()
                                                     }
@@ -412,7 +757,7 @@ impl From<near_views::ExecutionStatusView> for execution_outcome::Status {
                                         }
                                         ActionErrorKind::NewReceiptValidationError(rve) => {
                                             action_error::Kind::NewReceiptValidation {
-                                                0: NewReceiptValidationErrorKind { error: match rve {
+                                                0: NewReceiptValidationErrorKind { detail: error_detail(&rve), error: match rve {
                                                     near_errors::ReceiptValidationError::InvalidPredecessorId { .. } => {
                                                         ReceiptValidationError::InvalidPredecessorId.into()
                                                     }
@@ -477,9 +822,12 @@ impl From<near_views::ExecutionStatusView> for execution_outcome::Status {
                                                 0: Default::default(),
                                             }
                                         }
-                                        ActionErrorKind::DelegateActionAccessKeyError(_) => {
+                                        ActionErrorKind::DelegateActionAccessKeyError(e) => {
                                             action_error::Kind::DelegateActionAccessKeyError {
-                                                0: Default::default(),
+                                                0: DelegateActionAccessKeyErrorKind {
+                                                    detail: error_detail(&e),
+                                                    ..Default::default()
+                                                },
                                             }
                                         }
                                         ActionErrorKind::DelegateActionInvalidNonce { delegate_nonce, ak_nonce} => {
@@ -576,6 +924,12 @@ impl From<near_views::ExecutionStatusView> for execution_outcome::Status {
     }
 }
 
+/// JSON-RPC representation of a nearcore error, keeping the payload its protobuf kind
+/// drops.
+fn error_detail<T: serde::Serialize>(error: &T) -> String {
+    serde_json::to_string(error).unwrap_or_default()
+}
+
 impl From<near_primitives::merkle::MerklePath> for MerklePath {
     fn from(p: near_primitives::merkle::MerklePath) -> Self {
         MerklePath {
@@ -603,6 +957,7 @@ impl From<near_views::SignedTransactionView> for SignedTransaction {
             actions: tx.actions.into_iter().map(|a| Action::from(a)).collect(),
             signature: Some(tx.signature.into()),
             hash: Some(CryptoHash::from(tx.hash)),
+            priority_fee: tx.priority_fee,
         }
     }
 }
@@ -617,7 +972,11 @@ impl From<near_views::ActionView> for Action {
             },
             near_views::ActionView::DeployContract { code } => Action {
                 action: Some(action::Action::DeployContract {
-                    0: DeployContractAction { code: code.into() },
+                    0: DeployContractAction {
+                        code: code.into(),
+                        code_hash: vec![],
+                        code_len: 0,
+                    },
                 }),
             },
             near_views::ActionView::FunctionCall {
@@ -632,6 +991,8 @@ impl From<near_views::ActionView> for Action {
                         args: args.into(),
                         gas,
                         deposit: Some(BigInt::from(deposit)),
+                        args_trimmed: None,
+                        decoded_args: None,
                     },
                 }),
             },
@@ -748,8 +1109,41 @@ impl From<near_views::AccessKeyPermissionView> for AccessKeyPermission {
 
 impl From<&near_views::ChunkHeaderView> for ChunkHeader {
     fn from(ch: &near_views::ChunkHeaderView) -> Self {
-        let validator_proposals = &ch.validator_proposals;
+        ChunkHeader {
+            chunk_hash: Vec::from(ch.chunk_hash),
+            prev_block_hash: Vec::from(ch.prev_block_hash),
+            outcome_root: Vec::from(ch.outcome_root),
+            prev_state_root: Vec::from(ch.prev_state_root),
+            encoded_merkle_root: Vec::from(ch.encoded_merkle_root),
+            encoded_length: ch.encoded_length,
+            height_created: ch.height_created,
+            height_included: ch.height_included,
+            shard_id: ch.shard_id,
+            gas_used: ch.gas_used,
+            gas_limit: ch.gas_limit,
+            validator_reward: Some(BigInt::from(ch.validator_reward)),
+            balance_burnt: Some(BigInt::from(ch.balance_burnt)),
+            outgoing_receipts_root: Vec::from(ch.outgoing_receipts_root),
+            tx_root: Vec::from(ch.tx_root),
+            validator_proposals: ch
+                .validator_proposals
+                .iter()
+                .map(ValidatorStake::from)
+                .collect(),
+            signature: Some(Signature::from(&ch.signature)),
+            congestion_info: ch.congestion_info.as_ref().map(|info| CongestionInfo {
+                delayed_receipts_gas: Some(BigInt::from(info.delayed_receipts_gas)),
+                buffered_receipts_gas: Some(BigInt::from(info.buffered_receipts_gas)),
+                receipt_bytes: info.receipt_bytes,
+                allowed_shard: info.allowed_shard.into(),
+            }),
+        }
+    }
+}
 
+impl From<near_views::ChunkHeaderView> for ChunkHeader {
+    /// Moves the validator proposals out of the view.
+    fn from(ch: near_views::ChunkHeaderView) -> Self {
         ChunkHeader {
             chunk_hash: Vec::from(ch.chunk_hash),
             prev_block_hash: Vec::from(ch.prev_block_hash),
@@ -766,53 +1160,57 @@ impl From<&near_views::ChunkHeaderView> for ChunkHeader {
             balance_burnt: Some(BigInt::from(ch.balance_burnt)),
             outgoing_receipts_root: Vec::from(ch.outgoing_receipts_root),
             tx_root: Vec::from(ch.tx_root),
-            validator_proposals: validator_proposals
+            validator_proposals: ch
+                .validator_proposals
                 .into_iter()
-                .map(|vp| ValidatorStake::from(vp))
+                .map(ValidatorStake::from)
                 .collect(),
-            signature: Some(ch.signature.clone().into()),
+            signature: Some(Signature::from(ch.signature)),
+            congestion_info: ch.congestion_info.map(|info| CongestionInfo {
+                delayed_receipts_gas: Some(BigInt::from(info.delayed_receipts_gas)),
+                buffered_receipts_gas: Some(BigInt::from(info.buffered_receipts_gas)),
+                receipt_bytes: info.receipt_bytes,
+                allowed_shard: info.allowed_shard.into(),
+            }),
         }
     }
 }
 
 impl From<Box<NearSignature>> for Signature {
     fn from(sig: Box<NearSignature>) -> Self {
-        match *sig {
-            NearSignature::ED25519(s) => Signature {
-                r#type: CurveKind::Ed25519.into(),
-                bytes: Vec::from(s.to_bytes()),
-            } as Signature,
-            NearSignature::SECP256K1(s) => {
-                let data = Vec::from(<[u8; 65]>::from(s));
-                Signature {
-                    r#type: CurveKind::Secp256k1.into(),
-                    bytes: data,
-                }
-            }
-        }
+        Signature::from(sig.as_ref())
     }
 }
 
 impl From<NearSignature> for Signature {
     fn from(sig: NearSignature) -> Self {
+        Signature::from(&sig)
+    }
+}
+
+impl From<&NearSignature> for Signature {
+    fn from(sig: &NearSignature) -> Self {
         match sig {
             NearSignature::ED25519(s) => Signature {
                 r#type: CurveKind::Ed25519.into(),
                 bytes: Vec::from(s.to_bytes()),
-            } as Signature,
-            NearSignature::SECP256K1(s) => {
-                let data = Vec::from(<[u8; 65]>::from(s));
-                Signature {
-                    r#type: CurveKind::Secp256k1.into(),
-                    bytes: data,
-                }
-            }
+            },
+            NearSignature::SECP256K1(s) => Signature {
+                r#type: CurveKind::Secp256k1.into(),
+                bytes: Vec::from(<[u8; 65]>::from(s.clone())),
+            },
         }
     }
 }
 
 impl From<NearPublicKey> for PublicKey {
     fn from(key: NearPublicKey) -> Self {
+        PublicKey::from(&key)
+    }
+}
+
+impl From<&NearPublicKey> for PublicKey {
+    fn from(key: &NearPublicKey) -> Self {
         match key {
             NearPublicKey::ED25519(s) => PublicKey {
                 r#type: CurveKind::Ed25519.into(),
@@ -826,6 +1224,15 @@ impl From<NearPublicKey> for PublicKey {
     }
 }
 
+impl From<near_primitives::challenge::SlashedValidator> for SlashedValidator {
+    fn from(sv: near_primitives::challenge::SlashedValidator) -> Self {
+        SlashedValidator {
+            account_id: sv.account_id.into(),
+            is_double_sign: sv.is_double_sign,
+        }
+    }
+}
+
 impl From<&near_primitives::challenge::SlashedValidator> for SlashedValidator {
     fn from(sv: &near_primitives::challenge::SlashedValidator) -> Self {
         SlashedValidator {
@@ -835,13 +1242,27 @@ impl From<&near_primitives::challenge::SlashedValidator> for SlashedValidator {
     }
 }
 
+impl From<near_primitives::views::validator_stake_view::ValidatorStakeView> for ValidatorStake {
+    fn from(sv: near_primitives::views::validator_stake_view::ValidatorStakeView) -> Self {
+        match sv {
+            near_primitives::views::validator_stake_view::ValidatorStakeView::V1(v) => {
+                ValidatorStake {
+                    account_id: v.account_id.into(),
+                    public_key: Some(PublicKey::from(v.public_key)),
+                    stake: Some(BigInt::from(v.stake)),
+                }
+            }
+        }
+    }
+}
+
 impl From<&near_primitives::views::validator_stake_view::ValidatorStakeView> for ValidatorStake {
     fn from(sv: &near_primitives::views::validator_stake_view::ValidatorStakeView) -> Self {
         match sv {
             near_primitives::views::validator_stake_view::ValidatorStakeView::V1(v) => {
                 ValidatorStake {
                     account_id: v.account_id.to_string(),
-                    public_key: Some(PublicKey::from(v.public_key.clone())),
+                    public_key: Some(PublicKey::from(&v.public_key)),
                     stake: Some(BigInt::from(v.stake)),
                 }
             }
@@ -867,7 +1288,7 @@ impl From<near_primitives::hash::CryptoHash> for CryptoHash {
 
 impl Display for CryptoHash {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", hex::encode(&self.bytes))
+        write!(f, "{}", self.to_hex())
     }
 }
 
@@ -878,3 +1299,101 @@ impl Display for Block {
         write!(f, "#{} ({})", header.height, header.hash.as_ref().unwrap())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn blocks_decode_as_header_only_blocks() {
+        let block = Block {
+            header: Some(BlockHeader {
+                height: 42,
+                ..Default::default()
+            }),
+            chunk_headers: vec![ChunkHeader {
+                chunk_hash: vec![1; 32],
+                ..Default::default()
+            }],
+            shards: vec![IndexerShard {
+                shard_id: 3,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let header_only = HeaderOnlyBlock::decode(block.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(header_only.header, block.header);
+        assert_eq!(header_only.chunk_headers, block.chunk_headers);
+
+        let shards_only = Block {
+            shards: block.shards.clone(),
+            ..Default::default()
+        };
+        let header_only = HeaderOnlyBlock::decode(shards_only.encode_to_vec().as_slice()).unwrap();
+        assert!(header_only.chunk_headers.is_empty());
+    }
+
+    #[test]
+    fn blocks_round_trip_through_json() {
+        let failure = FailureExecutionStatus {
+            failure: Some(failure_execution_status::Failure::InvalidTxError(
+                InvalidTxError::InvalidNonce.into(),
+            )),
+            ..Default::default()
+        };
+        let block = Block {
+            header: Some(BlockHeader {
+                height: 42,
+                ..Default::default()
+            }),
+            ft_transfers: vec![
+                FtTransfer {
+                    kind: FtTransferKind::Mint.into(),
+                    status: ReceiptStatus::Success.into(),
+                    amount: Some(BigInt::from(10u128)),
+                    ..Default::default()
+                },
+                FtTransfer {
+                    kind: 99,
+                    ..Default::default()
+                },
+            ],
+            shards: vec![IndexerShard {
+                receipt_execution_outcomes: vec![IndexerExecutionOutcomeWithReceipt {
+                    execution_outcome: Some(ExecutionOutcomeWithId {
+                        outcome: Some(ExecutionOutcome {
+                            status: Some(execution_outcome::Status::Failure(failure)),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(&block).unwrap();
+        assert_eq!(json["ftTransfers"][0]["kind"], "mint");
+        assert_eq!(json["ftTransfers"][0]["status"], "success");
+        assert_eq!(json["ftTransfers"][1]["kind"], 99);
+        let outcome = &json["shards"][0]["receiptExecutionOutcomes"][0]["executionOutcome"];
+        assert_eq!(
+            outcome["outcome"]["failure"]["invalidTxError"],
+            "invalidNonce"
+        );
+        assert_eq!(serde_json::from_value::<Block>(json).unwrap(), block);
+
+        let partial: Block =
+            serde_json::from_str(r#"{"header": {"height": 42}, "ftTransfers": [{"kind": 3}]}"#)
+                .unwrap();
+        assert_eq!(partial.header.unwrap().height, 42);
+        assert_eq!(
+            partial.ft_transfers[0].kind,
+            i32::from(FtTransferKind::Mint)
+        );
+    }
+}
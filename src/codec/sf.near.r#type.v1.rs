@@ -1,4 +1,7 @@
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct Block {
     #[prost(string, tag = "1")]
     pub author: ::prost::alloc::string::String,
@@ -10,6 +13,33 @@ pub struct Block {
     pub shards: ::prost::alloc::vec::Vec<IndexerShard>,
     #[prost(message, repeated, tag = "5")]
     pub state_changes: ::prost::alloc::vec::Vec<StateChangeWithCause>,
+    /// Set when transactions, receipts and outcomes not matching an account filter were
+    /// removed, the block is then not a full block.
+    #[prost(bool, tag = "6")]
+    pub filtering_applied: bool,
+    /// SHA-256 of the account filter rules applied to the block.
+    #[prost(bytes = "vec", tag = "7")]
+    #[serde(with = "crate::codec::json::bytes")]
+    pub filter_hash: ::prost::alloc::vec::Vec<u8>,
+    /// NEP-297 events logged by the receipts of the block, in execution order.
+    #[prost(message, repeated, tag = "8")]
+    pub events: ::prost::alloc::vec::Vec<Event>,
+    /// NEP-141 fungible token transfers, mints and burns, in execution order.
+    #[prost(message, repeated, tag = "9")]
+    pub ft_transfers: ::prost::alloc::vec::Vec<FtTransfer>,
+    /// NEP-171 non-fungible token transfers, mints and burns, in execution order.
+    #[prost(message, repeated, tag = "10")]
+    pub nft_activities: ::prost::alloc::vec::Vec<NftActivity>,
+    /// Transactions whose last receipt was executed in this block.
+    #[prost(message, repeated, tag = "11")]
+    pub finalized_transactions: ::prost::alloc::vec::Vec<TransactionFinalized>,
+    /// Account balance changes, in state changes order.
+    #[prost(message, repeated, tag = "12")]
+    pub balance_changes: ::prost::alloc::vec::Vec<BalanceChange>,
+    /// Set when balance changes were requested but could not be derived, `balance_changes`
+    /// is then empty.
+    #[prost(string, tag = "13")]
+    pub balance_changes_error: ::prost::alloc::string::String,
 }
 /// HeaderOnlyBlock is a standard [Block] structure where all other fields are
 /// removed so that hydrating that object from a [Block] bytes payload will
@@ -17,63 +47,287 @@ pub struct Block {
 ///
 /// This can be used to unpack a [Block] when only the [BlockHeader] information
 /// is required and greatly reduced required memory.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct HeaderOnlyBlock {
     #[prost(message, optional, tag = "2")]
     pub header: ::core::option::Option<BlockHeader>,
+    /// Same tag as [Block] chunk headers (3, shards being 4) so that [Block] bytes decode
+    /// as a [HeaderOnlyBlock] keeping the chunk headers. Must follow [Block] if it changes.
+    #[prost(message, repeated, tag = "3")]
+    pub chunk_headers: ::prost::alloc::vec::Vec<ChunkHeader>,
 }
+/// NEP-297 event, logged by a receipt as an `EVENT_JSON:` prefixed JSON object.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct Event {
+    #[prost(string, tag = "1")]
+    pub standard: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub version: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub event: ::prost::alloc::string::String,
+    /// The event `data` member as JSON, empty when absent.
+    #[prost(string, tag = "4")]
+    pub data: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "5")]
+    pub receipt_id: ::core::option::Option<CryptoHash>,
+    #[prost(string, tag = "6")]
+    pub executor_id: ::prost::alloc::string::String,
+    /// Position of the event log within the receipt execution outcome logs.
+    #[prost(uint32, tag = "7")]
+    pub log_index: u32,
+}
+/// Fungible token movement, from a NEP-141 event or a legacy transfer log. Mints have no
+/// sender and burns no receiver.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct FtTransfer {
+    #[prost(enumeration = "FtTransferKind", tag = "1")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::FtTransferKind, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::FtTransferKind, _>"
+    )]
+    pub kind: i32,
+    /// Token contract, the executor of the receipt that logged the transfer.
+    #[prost(string, tag = "2")]
+    pub contract_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub sender_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub receiver_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "5")]
+    pub amount: ::core::option::Option<BigInt>,
+    #[prost(string, tag = "6")]
+    pub memo: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "7")]
+    pub receipt_id: ::core::option::Option<CryptoHash>,
+    /// Status of the receipt, logs of failed receipts are kept by nearcore but their
+    /// transfers did not happen.
+    #[prost(enumeration = "ReceiptStatus", tag = "8")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::ReceiptStatus, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::ReceiptStatus, _>"
+    )]
+    pub status: i32,
+    #[prost(uint32, tag = "9")]
+    pub log_index: u32,
+}
+/// Non-fungible token movement from a NEP-171 event. Mints have no old owner and burns
+/// no new owner.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct NftActivity {
+    #[prost(enumeration = "NftActivityKind", tag = "1")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::NftActivityKind, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::NftActivityKind, _>"
+    )]
+    pub kind: i32,
+    /// Token contract, the executor of the receipt that logged the event.
+    #[prost(string, tag = "2")]
+    pub contract_id: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "3")]
+    pub token_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, tag = "4")]
+    pub old_owner_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub new_owner_id: ::prost::alloc::string::String,
+    /// Account approved by the owner that made the transfer or burn, if any.
+    #[prost(string, tag = "6")]
+    pub authorized_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "7")]
+    pub memo: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "8")]
+    pub receipt_id: ::core::option::Option<CryptoHash>,
+    /// Status of the receipt, activities of failed receipts were reverted.
+    #[prost(enumeration = "ReceiptStatus", tag = "9")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::ReceiptStatus, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::ReceiptStatus, _>"
+    )]
+    pub status: i32,
+    #[prost(uint32, tag = "10")]
+    pub log_index: u32,
+}
+/// Transaction all of whose receipts, refunds included, have been executed.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct TransactionFinalized {
+    #[prost(message, optional, tag = "1")]
+    pub transaction_hash: ::core::option::Option<CryptoHash>,
+    #[prost(string, tag = "2")]
+    pub signer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub receiver_id: ::prost::alloc::string::String,
+    /// Outcome of the transaction, the status of the last receipt of its
+    /// `SuccessReceiptId` chain.
+    #[prost(enumeration = "ReceiptStatus", tag = "4")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::ReceiptStatus, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::ReceiptStatus, _>"
+    )]
+    pub status: i32,
+    /// Value returned by the transaction when successful.
+    #[prost(bytes = "vec", tag = "5")]
+    #[serde(with = "crate::codec::json::bytes")]
+    pub value: ::prost::alloc::vec::Vec<u8>,
+    /// Height of the block that included the transaction.
+    #[prost(uint64, tag = "6")]
+    pub included_height: u64,
+    /// Number of receipts the transaction spawned, directly or not.
+    #[prost(uint32, tag = "7")]
+    pub receipt_count: u32,
+    /// Shard that executed the last receipt, or that included the transaction when it
+    /// spawned none.
+    #[prost(uint64, tag = "8")]
+    pub shard_id: u64,
+    /// Set when `value` was trimmed by the payload trimming policy, with the success
+    /// value limit of the transaction receiver.
+    #[prost(message, optional, tag = "9")]
+    pub value_trimmed: ::core::option::Option<TrimmedPayload>,
+}
+/// Change of the amount, locked amount or storage usage of an account, with the values
+/// before and after it.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct BalanceChange {
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "BalanceChangeCause", tag = "2")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::BalanceChangeCause, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::BalanceChangeCause, _>"
+    )]
+    pub cause: i32,
+    /// Set when the cause is a transaction.
+    #[prost(message, optional, tag = "3")]
+    pub transaction_hash: ::core::option::Option<CryptoHash>,
+    /// Set when the cause is a receipt, a gas refund or a contract reward.
+    #[prost(message, optional, tag = "4")]
+    pub receipt_id: ::core::option::Option<CryptoHash>,
+    #[prost(message, optional, tag = "5")]
+    pub amount_before: ::core::option::Option<BigInt>,
+    #[prost(message, optional, tag = "6")]
+    pub amount_after: ::core::option::Option<BigInt>,
+    #[prost(message, optional, tag = "7")]
+    pub locked_before: ::core::option::Option<BigInt>,
+    #[prost(message, optional, tag = "8")]
+    pub locked_after: ::core::option::Option<BigInt>,
+    #[prost(uint64, tag = "9")]
+    pub storage_usage_before: u64,
+    #[prost(uint64, tag = "10")]
+    pub storage_usage_after: u64,
+    /// Shard of the account.
+    #[prost(uint64, tag = "11")]
+    pub shard_id: u64,
+}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct StateChangeWithCause {
     #[prost(message, optional, tag = "1")]
     pub value: ::core::option::Option<StateChangeValue>,
     #[prost(message, optional, tag = "2")]
     pub cause: ::core::option::Option<StateChangeCause>,
+    /// Shard the state change happened in.
+    #[prost(uint64, tag = "3")]
+    pub shard_id: u64,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct StateChangeCause {
     #[prost(
         oneof = "state_change_cause::Cause",
         tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10"
     )]
+    #[serde(flatten)]
     pub cause: ::core::option::Option<state_change_cause::Cause>,
 }
 /// Nested message and enum types in `StateChangeCause`.
 pub mod state_change_cause {
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct NotWritableToDisk {}
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct InitialState {}
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct TransactionProcessing {
         #[prost(message, optional, tag = "1")]
         pub tx_hash: ::core::option::Option<super::CryptoHash>,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct ActionReceiptProcessingStarted {
         #[prost(message, optional, tag = "1")]
         pub receipt_hash: ::core::option::Option<super::CryptoHash>,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct ActionReceiptGasReward {
         #[prost(message, optional, tag = "1")]
         pub tx_hash: ::core::option::Option<super::CryptoHash>,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct ReceiptProcessing {
         #[prost(message, optional, tag = "1")]
         pub tx_hash: ::core::option::Option<super::CryptoHash>,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct PostponedReceipt {
         #[prost(message, optional, tag = "1")]
         pub tx_hash: ::core::option::Option<super::CryptoHash>,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct UpdatedDelayedReceipts {}
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct ValidatorAccountsUpdate {}
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct Migration {}
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Cause {
         #[prost(message, tag = "1")]
@@ -98,26 +352,39 @@ pub mod state_change_cause {
         Migration(Migration),
     }
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct StateChangeValue {
     #[prost(oneof = "state_change_value::Value", tags = "1, 2, 3, 4, 5, 6, 7, 8")]
+    #[serde(flatten)]
     pub value: ::core::option::Option<state_change_value::Value>,
 }
 /// Nested message and enum types in `StateChangeValue`.
 pub mod state_change_value {
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct AccountUpdate {
         #[prost(string, tag = "1")]
         pub account_id: ::prost::alloc::string::String,
         #[prost(message, optional, tag = "2")]
         pub account: ::core::option::Option<super::Account>,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct AccountDeletion {
         #[prost(string, tag = "1")]
         pub account_id: ::prost::alloc::string::String,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct AccessKeyUpdate {
         #[prost(string, tag = "1")]
         pub account_id: ::prost::alloc::string::String,
@@ -126,41 +393,68 @@ pub mod state_change_value {
         #[prost(message, optional, tag = "3")]
         pub access_key: ::core::option::Option<super::AccessKey>,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct AccessKeyDeletion {
         #[prost(string, tag = "1")]
         pub account_id: ::prost::alloc::string::String,
         #[prost(message, optional, tag = "2")]
         pub public_key: ::core::option::Option<super::PublicKey>,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct DataUpdate {
         #[prost(string, tag = "1")]
         pub account_id: ::prost::alloc::string::String,
         #[prost(bytes = "vec", tag = "2")]
+        #[serde(with = "crate::codec::json::bytes")]
         pub key: ::prost::alloc::vec::Vec<u8>,
         #[prost(bytes = "vec", tag = "3")]
+        #[serde(with = "crate::codec::json::bytes")]
         pub value: ::prost::alloc::vec::Vec<u8>,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct DataDeletion {
         #[prost(string, tag = "1")]
         pub account_id: ::prost::alloc::string::String,
         #[prost(bytes = "vec", tag = "2")]
+        #[serde(with = "crate::codec::json::bytes")]
         pub key: ::prost::alloc::vec::Vec<u8>,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct ContractCodeUpdate {
         #[prost(string, tag = "1")]
         pub account_id: ::prost::alloc::string::String,
+        /// Empty when contract code is deduplicated, like `DeployContractAction.code`.
         #[prost(bytes = "vec", tag = "2")]
+        #[serde(with = "crate::codec::json::bytes")]
         pub code: ::prost::alloc::vec::Vec<u8>,
+        #[prost(bytes = "vec", tag = "3")]
+        #[serde(with = "crate::codec::json::bytes")]
+        pub code_hash: ::prost::alloc::vec::Vec<u8>,
+        #[prost(uint64, tag = "4")]
+        pub code_len: u64,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[serde(default)]
     pub struct ContractCodeDeletion {
         #[prost(string, tag = "1")]
         pub account_id: ::prost::alloc::string::String,
     }
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Value {
         #[prost(message, tag = "1")]
@@ -181,7 +475,10 @@ pub mod state_change_value {
         ContractDeletion(ContractCodeDeletion),
     }
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct Account {
     #[prost(message, optional, tag = "1")]
     pub amount: ::core::option::Option<BigInt>,
@@ -192,7 +489,10 @@ pub struct Account {
     #[prost(uint64, tag = "4")]
     pub storage_usage: u64,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct BlockHeader {
     #[prost(uint64, tag = "1")]
     pub height: u64,
@@ -251,6 +551,7 @@ pub struct BlockHeader {
     #[prost(message, optional, tag = "28")]
     pub block_merkle_root: ::core::option::Option<CryptoHash>,
     #[prost(bytes = "vec", tag = "29")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub epoch_sync_data_hash: ::prost::alloc::vec::Vec<u8>,
     #[prost(message, repeated, tag = "30")]
     pub approvals: ::prost::alloc::vec::Vec<Signature>,
@@ -258,32 +559,94 @@ pub struct BlockHeader {
     pub signature: ::core::option::Option<Signature>,
     #[prost(uint32, tag = "32")]
     pub latest_protocol_version: u32,
+    /// Approvals in block producer order, missing approvals are kept without a signature.
+    #[prost(message, repeated, tag = "33")]
+    pub block_approvals: ::prost::alloc::vec::Vec<BlockApproval>,
+    /// Absent on blocks produced before block bodies were hashed.
+    #[prost(message, optional, tag = "34")]
+    pub block_body_hash: ::core::option::Option<CryptoHash>,
+    /// Absent on blocks produced before chunks were endorsed.
+    #[prost(message, optional, tag = "35")]
+    pub chunk_endorsements: ::core::option::Option<ChunkEndorsements>,
+    /// Set when approvals were to be paired with block producers but could not be, account
+    /// IDs in `block_approvals` are then empty.
+    #[prost(string, tag = "36")]
+    pub block_approvals_error: ::prost::alloc::string::String,
+}
+/// Chunk endorsement bitmaps of a block, one per shard.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct ChunkEndorsements {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    #[serde(with = "crate::codec::json::repeated_bytes")]
+    pub bitmaps: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct BlockApproval {
+    /// Account of the block producer, empty when it could not be resolved.
+    #[prost(string, tag = "1")]
+    pub account_id: ::prost::alloc::string::String,
+    /// Unset when the block producer did not approve the block.
+    #[prost(message, optional, tag = "2")]
+    pub signature: ::core::option::Option<Signature>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(into = "String", try_from = "String")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct BigInt {
     #[prost(bytes = "vec", tag = "1")]
     pub bytes: ::prost::alloc::vec::Vec<u8>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(into = "String", try_from = "String")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct CryptoHash {
     #[prost(bytes = "vec", tag = "1")]
     pub bytes: ::prost::alloc::vec::Vec<u8>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct Signature {
     #[prost(enumeration = "CurveKind", tag = "1")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::CurveKind, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::CurveKind, _>"
+    )]
     pub r#type: i32,
     #[prost(bytes = "vec", tag = "2")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub bytes: ::prost::alloc::vec::Vec<u8>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct PublicKey {
     #[prost(enumeration = "CurveKind", tag = "1")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::CurveKind, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::CurveKind, _>"
+    )]
     pub r#type: i32,
     #[prost(bytes = "vec", tag = "2")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub bytes: ::prost::alloc::vec::Vec<u8>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct ValidatorStake {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
@@ -292,24 +655,35 @@ pub struct ValidatorStake {
     #[prost(message, optional, tag = "3")]
     pub stake: ::core::option::Option<BigInt>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct SlashedValidator {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
     #[prost(bool, tag = "2")]
     pub is_double_sign: bool,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct ChunkHeader {
     #[prost(bytes = "vec", tag = "1")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub chunk_hash: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", tag = "2")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub prev_block_hash: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", tag = "3")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub outcome_root: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", tag = "4")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub prev_state_root: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", tag = "5")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub encoded_merkle_root: ::prost::alloc::vec::Vec<u8>,
     #[prost(uint64, tag = "6")]
     pub encoded_length: u64,
@@ -328,15 +702,37 @@ pub struct ChunkHeader {
     #[prost(message, optional, tag = "13")]
     pub balance_burnt: ::core::option::Option<BigInt>,
     #[prost(bytes = "vec", tag = "14")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub outgoing_receipts_root: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", tag = "15")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub tx_root: ::prost::alloc::vec::Vec<u8>,
     #[prost(message, repeated, tag = "16")]
     pub validator_proposals: ::prost::alloc::vec::Vec<ValidatorStake>,
     #[prost(message, optional, tag = "17")]
     pub signature: ::core::option::Option<Signature>,
+    /// Absent on chunks produced before congestion control.
+    #[prost(message, optional, tag = "18")]
+    pub congestion_info: ::core::option::Option<CongestionInfo>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct CongestionInfo {
+    #[prost(message, optional, tag = "1")]
+    pub delayed_receipts_gas: ::core::option::Option<BigInt>,
+    #[prost(message, optional, tag = "2")]
+    pub buffered_receipts_gas: ::core::option::Option<BigInt>,
+    #[prost(uint64, tag = "3")]
+    pub receipt_bytes: u64,
+    #[prost(uint32, tag = "4")]
+    pub allowed_shard: u32,
+}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct IndexerShard {
     #[prost(uint64, tag = "1")]
     pub shard_id: u64,
@@ -345,14 +741,27 @@ pub struct IndexerShard {
     #[prost(message, repeated, tag = "3")]
     pub receipt_execution_outcomes: ::prost::alloc::vec::Vec<IndexerExecutionOutcomeWithReceipt>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct IndexerExecutionOutcomeWithReceipt {
     #[prost(message, optional, tag = "1")]
     pub execution_outcome: ::core::option::Option<ExecutionOutcomeWithId>,
     #[prost(message, optional, tag = "2")]
     pub receipt: ::core::option::Option<Receipt>,
+    /// Hash of the transaction the receipt descends from, unset when unknown.
+    #[prost(message, optional, tag = "3")]
+    pub transaction_hash: ::core::option::Option<CryptoHash>,
+    /// Depth of the receipt in the transaction receipt tree, receipts created by the
+    /// transaction itself being at depth 1, 0 when unknown.
+    #[prost(uint32, tag = "4")]
+    pub receipt_depth: u32,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct IndexerChunk {
     #[prost(string, tag = "1")]
     pub author: ::prost::alloc::string::String,
@@ -363,14 +772,20 @@ pub struct IndexerChunk {
     #[prost(message, repeated, tag = "4")]
     pub receipts: ::prost::alloc::vec::Vec<Receipt>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct IndexerTransactionWithOutcome {
     #[prost(message, optional, tag = "1")]
     pub transaction: ::core::option::Option<SignedTransaction>,
     #[prost(message, optional, tag = "2")]
     pub outcome: ::core::option::Option<IndexerExecutionOutcomeWithOptionalReceipt>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct SignedTransaction {
     #[prost(string, tag = "1")]
     pub signer_id: ::prost::alloc::string::String,
@@ -386,15 +801,23 @@ pub struct SignedTransaction {
     pub signature: ::core::option::Option<Signature>,
     #[prost(message, optional, tag = "7")]
     pub hash: ::core::option::Option<CryptoHash>,
+    #[prost(uint64, tag = "8")]
+    pub priority_fee: u64,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct IndexerExecutionOutcomeWithOptionalReceipt {
     #[prost(message, optional, tag = "1")]
     pub execution_outcome: ::core::option::Option<ExecutionOutcomeWithId>,
     #[prost(message, optional, tag = "2")]
     pub receipt: ::core::option::Option<Receipt>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct Receipt {
     #[prost(string, tag = "1")]
     pub predecessor_id: ::prost::alloc::string::String,
@@ -402,11 +825,16 @@ pub struct Receipt {
     pub receiver_id: ::prost::alloc::string::String,
     #[prost(message, optional, tag = "3")]
     pub receipt_id: ::core::option::Option<CryptoHash>,
+    #[prost(uint64, tag = "4")]
+    pub priority: u64,
     #[prost(oneof = "receipt::Receipt", tags = "10, 11")]
+    #[serde(flatten)]
     pub receipt: ::core::option::Option<receipt::Receipt>,
 }
 /// Nested message and enum types in `Receipt`.
 pub mod receipt {
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Receipt {
         #[prost(message, tag = "10")]
@@ -415,14 +843,27 @@ pub mod receipt {
         Data(super::ReceiptData),
     }
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct ReceiptData {
     #[prost(message, optional, tag = "1")]
     pub data_id: ::core::option::Option<CryptoHash>,
-    #[prost(bytes = "vec", tag = "2")]
-    pub data: ::prost::alloc::vec::Vec<u8>,
+    /// Absent when the receipt carries no data, which is not the same as empty data.
+    #[prost(bytes = "vec", optional, tag = "2")]
+    #[serde(default, with = "crate::codec::json::optional_bytes")]
+    pub data: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Set when `data` was trimmed by the payload trimming policy.
+    #[prost(message, optional, tag = "3")]
+    pub data_trimmed: ::core::option::Option<TrimmedPayload>,
+    #[prost(bool, tag = "4")]
+    pub is_promise_resume: bool,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct ReceiptAction {
     #[prost(string, tag = "1")]
     pub signer_id: ::prost::alloc::string::String,
@@ -436,15 +877,23 @@ pub struct ReceiptAction {
     pub input_data_ids: ::prost::alloc::vec::Vec<CryptoHash>,
     #[prost(message, repeated, tag = "6")]
     pub actions: ::prost::alloc::vec::Vec<Action>,
+    #[prost(bool, tag = "7")]
+    pub is_promise_yield: bool,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DataReceiver {
     #[prost(message, optional, tag = "1")]
     pub data_id: ::core::option::Option<CryptoHash>,
     #[prost(string, tag = "2")]
     pub receiver_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct ExecutionOutcomeWithId {
     #[prost(message, optional, tag = "1")]
     pub proof: ::core::option::Option<MerklePath>,
@@ -455,7 +904,10 @@ pub struct ExecutionOutcomeWithId {
     #[prost(message, optional, tag = "4")]
     pub outcome: ::core::option::Option<ExecutionOutcome>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct ExecutionOutcome {
     #[prost(string, repeated, tag = "1")]
     pub logs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
@@ -468,12 +920,25 @@ pub struct ExecutionOutcome {
     #[prost(string, tag = "5")]
     pub executor_id: ::prost::alloc::string::String,
     #[prost(enumeration = "ExecutionMetadata", tag = "6")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::ExecutionMetadata, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::ExecutionMetadata, _>"
+    )]
     pub metadata: i32,
+    /// Logs trimmed by the payload trimming policy, in `logs` order.
+    #[prost(message, repeated, tag = "7")]
+    pub trimmed_logs: ::prost::alloc::vec::Vec<TrimmedLog>,
+    /// Gas profile of V2 and V3 metadata, empty for V1.
+    #[prost(message, repeated, tag = "8")]
+    pub gas_profile: ::prost::alloc::vec::Vec<CostGasUsed>,
     #[prost(oneof = "execution_outcome::Status", tags = "20, 21, 22, 23")]
+    #[serde(flatten)]
     pub status: ::core::option::Option<execution_outcome::Status>,
 }
 /// Nested message and enum types in `ExecutionOutcome`.
 pub mod execution_outcome {
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Status {
         #[prost(message, tag = "20")]
@@ -486,45 +951,117 @@ pub mod execution_outcome {
         SuccessReceiptId(super::SuccessReceiptIdExecutionStatus),
     }
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct SuccessValueExecutionStatus {
     #[prost(bytes = "vec", tag = "1")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub value: ::prost::alloc::vec::Vec<u8>,
+    /// Set when `value` was trimmed by the payload trimming policy.
+    #[prost(message, optional, tag = "2")]
+    pub value_trimmed: ::core::option::Option<TrimmedPayload>,
+}
+/// Payload cut down to the byte limit of the payload trimming policy, the prefix within
+/// the limit is kept in place of the full payload.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct TrimmedPayload {
+    /// SHA-256 of the full payload.
+    #[prost(bytes = "vec", tag = "1")]
+    #[serde(with = "crate::codec::json::bytes")]
+    pub hash: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub original_length: u64,
 }
+/// Trimmed log of an execution outcome.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct TrimmedLog {
+    /// Position of the log in `logs`.
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    #[prost(message, optional, tag = "2")]
+    pub payload: ::core::option::Option<TrimmedPayload>,
+}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct SuccessReceiptIdExecutionStatus {
     #[prost(message, optional, tag = "1")]
     pub id: ::core::option::Option<CryptoHash>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct UnknownExecutionStatus {}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct FailureExecutionStatus {
+    /// JSON-RPC representation of the invalid transaction error, with its payload.
+    #[prost(string, tag = "3")]
+    pub invalid_tx_error_detail: ::prost::alloc::string::String,
     #[prost(oneof = "failure_execution_status::Failure", tags = "1, 2")]
+    #[serde(flatten)]
     pub failure: ::core::option::Option<failure_execution_status::Failure>,
 }
 /// Nested message and enum types in `FailureExecutionStatus`.
 pub mod failure_execution_status {
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Failure {
         #[prost(message, tag = "1")]
         ActionError(super::ActionError),
         #[prost(enumeration = "super::InvalidTxError", tag = "2")]
+        #[serde(
+            serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::InvalidTxError, _>",
+            deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::InvalidTxError, _>"
+        )]
         InvalidTxError(i32),
     }
 }
+/// Gas used by one cost of a receipt execution.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct CostGasUsed {
+    #[prost(string, tag = "1")]
+    pub cost_category: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub cost: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub gas_used: u64,
+}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct ActionError {
-    #[prost(uint64, tag = "1")]
-    pub index: u64,
+    /// Absent when the error is not tied to an action of the receipt.
+    #[prost(uint64, optional, tag = "1")]
+    pub index: ::core::option::Option<u64>,
     #[prost(
         oneof = "action_error::Kind",
         tags = "21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43"
     )]
+    #[serde(flatten)]
     pub kind: ::core::option::Option<action_error::Kind>,
 }
 /// Nested message and enum types in `ActionError`.
 pub mod action_error {
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Kind {
         #[prost(message, tag = "21")]
@@ -577,18 +1114,27 @@ pub mod action_error {
         NonRefundableTransferToExistingAccount(super::NonRefundableTransferToExistingAccountKind),
     }
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct AccountAlreadyExistsErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct AccountDoesNotExistErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
 }
 //// A top-level account ID can only be created by registrar.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct CreateAccountOnlyByRegistrarErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
@@ -597,52 +1143,76 @@ pub struct CreateAccountOnlyByRegistrarErrorKind {
     #[prost(string, tag = "3")]
     pub predecessor_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct CreateAccountNotAllowedErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub predecessor_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct ActorNoPermissionErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub actor_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DeleteKeyDoesNotExistErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
     #[prost(message, optional, tag = "2")]
     pub public_key: ::core::option::Option<PublicKey>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct AddKeyAlreadyExistsErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
     #[prost(message, optional, tag = "2")]
     pub public_key: ::core::option::Option<PublicKey>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DeleteAccountStakingErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct LackBalanceForStateErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
     #[prost(message, optional, tag = "2")]
     pub balance: ::core::option::Option<BigInt>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct TriesToUnstakeErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct TriesToStakeErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
@@ -653,7 +1223,10 @@ pub struct TriesToStakeErrorKind {
     #[prost(message, optional, tag = "4")]
     pub balance: ::core::option::Option<BigInt>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct InsufficientStakeErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
@@ -662,81 +1235,151 @@ pub struct InsufficientStakeErrorKind {
     #[prost(message, optional, tag = "3")]
     pub minimum_stake: ::core::option::Option<BigInt>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct FunctionCallErrorKind {
     #[prost(enumeration = "FunctionCallErrorSer", tag = "1")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::FunctionCallErrorSer, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::FunctionCallErrorSer, _>"
+    )]
     pub error: i32,
+    /// JSON-RPC representation of the function call error, with its payload.
+    #[prost(string, tag = "2")]
+    pub detail: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct NewReceiptValidationErrorKind {
     #[prost(enumeration = "ReceiptValidationError", tag = "1")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::ReceiptValidationError, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::ReceiptValidationError, _>"
+    )]
     pub error: i32,
+    /// JSON-RPC representation of the receipt validation error, with its payload.
+    #[prost(string, tag = "2")]
+    pub detail: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct OnlyImplicitAccountCreationAllowedErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DeleteAccountWithLargeStateErrorKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DelegateActionInvalidSignatureKind {}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DelegateActionSenderDoesNotMatchTxReceiverKind {
     #[prost(string, tag = "1")]
     pub sender_id: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub receiver_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DelegateActionExpiredKind {}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DelegateActionAccessKeyErrorKind {
     /// InvalidAccessKeyError
     #[prost(enumeration = "InvalidTxError", tag = "1")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::InvalidTxError, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::InvalidTxError, _>"
+    )]
     pub error: i32,
+    /// JSON-RPC representation of the access key error, with its payload.
+    #[prost(string, tag = "2")]
+    pub detail: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DelegateActionInvalidNonceKind {
     #[prost(uint64, tag = "1")]
     pub delegate_nonce: u64,
     #[prost(uint64, tag = "2")]
     pub ak_nonce: u64,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DelegateActionNonceTooLargeKind {
     #[prost(uint64, tag = "1")]
     pub delegate_nonce: u64,
     #[prost(uint64, tag = "2")]
     pub upper_bound: u64,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct NonRefundableTransferToExistingAccountKind {
     #[prost(string, tag = "1")]
     pub account_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct MerklePath {
     #[prost(message, repeated, tag = "1")]
     pub path: ::prost::alloc::vec::Vec<MerklePathItem>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct MerklePathItem {
     #[prost(message, optional, tag = "1")]
     pub hash: ::core::option::Option<CryptoHash>,
     #[prost(enumeration = "Direction", tag = "2")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::Direction, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::Direction, _>"
+    )]
     pub direction: i32,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct Action {
     #[prost(oneof = "action::Action", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9")]
+    #[serde(flatten)]
     pub action: ::core::option::Option<action::Action>,
 }
 /// Nested message and enum types in `Action`.
 pub mod action {
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Action {
         #[prost(message, tag = "1")]
@@ -759,61 +1402,123 @@ pub mod action {
         Delegate(super::SignedDelegateAction),
     }
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct CreateAccountAction {}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DeployContractAction {
+    /// Empty when contract code is deduplicated, the code is then in the contract code
+    /// store under `code_hash`.
     #[prost(bytes = "vec", tag = "1")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub code: ::prost::alloc::vec::Vec<u8>,
+    /// SHA-256 of the code, set when contract code is deduplicated.
+    #[prost(bytes = "vec", tag = "2")]
+    #[serde(with = "crate::codec::json::bytes")]
+    pub code_hash: ::prost::alloc::vec::Vec<u8>,
+    /// Length of the code, set when contract code is deduplicated.
+    #[prost(uint64, tag = "3")]
+    pub code_len: u64,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct FunctionCallAction {
     #[prost(string, tag = "1")]
     pub method_name: ::prost::alloc::string::String,
     #[prost(bytes = "vec", tag = "2")]
+    #[serde(with = "crate::codec::json::bytes")]
     pub args: ::prost::alloc::vec::Vec<u8>,
     #[prost(uint64, tag = "3")]
     pub gas: u64,
     #[prost(message, optional, tag = "4")]
     pub deposit: ::core::option::Option<BigInt>,
+    /// Set when `args` were trimmed by the payload trimming policy.
+    #[prost(message, optional, tag = "5")]
+    pub args_trimmed: ::core::option::Option<TrimmedPayload>,
+    /// Set when args decoding is enabled and `args` could be decoded.
+    #[prost(message, optional, tag = "6")]
+    pub decoded_args: ::core::option::Option<DecodedArgs>,
+}
+/// Function call args decoded to JSON.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
+pub struct DecodedArgs {
+    #[prost(enumeration = "ArgsDecoding", tag = "1")]
+    #[serde(
+        serialize_with = "crate::codec::json::enumeration::serialize::<crate::codec::ArgsDecoding, _>",
+        deserialize_with = "crate::codec::json::enumeration::deserialize::<crate::codec::ArgsDecoding, _>"
+    )]
+    pub decoding: i32,
+    #[prost(string, tag = "2")]
+    pub json: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct TransferAction {
     #[prost(message, optional, tag = "1")]
     pub deposit: ::core::option::Option<BigInt>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct StakeAction {
     #[prost(message, optional, tag = "1")]
     pub stake: ::core::option::Option<BigInt>,
     #[prost(message, optional, tag = "2")]
     pub public_key: ::core::option::Option<PublicKey>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct AddKeyAction {
     #[prost(message, optional, tag = "1")]
     pub public_key: ::core::option::Option<PublicKey>,
     #[prost(message, optional, tag = "2")]
     pub access_key: ::core::option::Option<AccessKey>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DeleteKeyAction {
     #[prost(message, optional, tag = "1")]
     pub public_key: ::core::option::Option<PublicKey>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DeleteAccountAction {
     #[prost(string, tag = "1")]
     pub beneficiary_id: ::prost::alloc::string::String,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct SignedDelegateAction {
     #[prost(message, optional, tag = "1")]
     pub signature: ::core::option::Option<Signature>,
     #[prost(message, optional, tag = "2")]
     pub delegate_action: ::core::option::Option<DelegateAction>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct DelegateAction {
     #[prost(string, tag = "1")]
     pub sender_id: ::prost::alloc::string::String,
@@ -828,20 +1533,29 @@ pub struct DelegateAction {
     #[prost(message, optional, tag = "6")]
     pub public_key: ::core::option::Option<PublicKey>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct AccessKey {
     #[prost(uint64, tag = "1")]
     pub nonce: u64,
     #[prost(message, optional, tag = "2")]
     pub permission: ::core::option::Option<AccessKeyPermission>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct AccessKeyPermission {
     #[prost(oneof = "access_key_permission::Permission", tags = "1, 2")]
+    #[serde(flatten)]
     pub permission: ::core::option::Option<access_key_permission::Permission>,
 }
 /// Nested message and enum types in `AccessKeyPermission`.
 pub mod access_key_permission {
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Permission {
         #[prost(message, tag = "1")]
@@ -850,7 +1564,10 @@ pub mod access_key_permission {
         FullAccess(super::FullAccessPermission),
     }
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct FunctionCallPermission {
     #[prost(message, optional, tag = "1")]
     pub allowance: ::core::option::Option<BigInt>,
@@ -859,20 +1576,32 @@ pub struct FunctionCallPermission {
     #[prost(string, repeated, tag = "3")]
     pub method_names: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[serde(default)]
 pub struct FullAccessPermission {}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum CurveKind {
     Ed25519 = 0,
     Secp256k1 = 1,
 }
+/// Version of the outcome metadata. The gas profile of V2 and V3 metadata is not kept.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum ExecutionMetadata {
     V1 = 0,
+    V2 = 1,
+    V3 = 2,
 }
 ///todo: add more detail?
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum FunctionCallErrorSer {
@@ -886,6 +1615,8 @@ pub enum FunctionCallErrorSer {
     ExecutionError = 7,
 }
 ///todo: add more detail?
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum ReceiptValidationError {
@@ -899,6 +1630,8 @@ pub enum ReceiptValidationError {
     ReceiptSizeExceeded = 7,
 }
 ///todo: add more detail?
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum InvalidTxError {
@@ -921,9 +1654,73 @@ pub enum InvalidTxError {
     ShardCongested = 16,
     ShardStuck = 17,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum Direction {
     Left = 0,
     Right = 1,
 }
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ReceiptStatus {
+    Unknown = 0,
+    Success = 1,
+    Failure = 2,
+}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FtTransferKind {
+    Transfer = 0,
+    /// Transfer made by `ft_transfer_call`.
+    TransferCall = 1,
+    /// Transfer back to the sender made by `ft_resolve_transfer` after `ft_transfer_call`.
+    Refund = 2,
+    Mint = 3,
+    Burn = 4,
+}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum NftActivityKind {
+    Transfer = 0,
+    Mint = 1,
+    Burn = 2,
+}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum BalanceChangeCause {
+    /// Initial state, migrations, delayed receipts updates and resharding.
+    Other = 0,
+    /// Gas and deposit charged to the signer of a transaction.
+    Transaction = 1,
+    Receipt = 2,
+    /// Receipt from `system` refunding unused gas or a failed deposit.
+    GasRefund = 3,
+    /// Share of the gas burnt by a receipt rewarded to the contract.
+    ContractReward = 4,
+    /// Validator rewards and stake updates at the start of an epoch.
+    ValidatorReward = 5,
+}
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ArgsDecoding {
+    /// Not set, never written by the indexer.
+    Unspecified = 0,
+    /// Args are UTF-8 JSON.
+    Json = 1,
+    /// Args are UTF-8 JSON with base64 encoded JSON string values, decoded in place.
+    Base64Json = 2,
+    /// Args are borsh serialized, decoded with the contract ABI.
+    BorshAbi = 3,
+}
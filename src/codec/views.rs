@@ -0,0 +1,1833 @@
+//! Conversions from the `sf.near.type.v1` model back to the nearcore view types.
+//!
+//! A view is only rebuilt when the protobuf model kept everything nearcore carries, so
+//! that the resulting views can be fed to nearcore-native code such as JSON-RPC
+//! compatible serializers. Values the model drops, such as trimmed payloads, code moved
+//! to the contract code store or the error payloads of blocks written before they were
+//! kept, fail the conversion with [ConversionError::Unrecoverable] instead of being made
+//! up.
+
+use super::*;
+use near_crypto::{ED25519PublicKey, KeyType, Secp256K1PublicKey};
+use near_indexer::near_primitives::types::AccountId;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversionError {
+    MissingField(&'static str),
+    InvalidHash(usize),
+    InvalidBigInt(BigIntError),
+    InvalidAccountId(String),
+    InvalidKey(String),
+    InvalidSignature(String),
+    InvalidEnumValue(&'static str, i32),
+    InvalidEncoding(String),
+    Unrecoverable(&'static str),
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConversionError::MissingField(field) => write!(f, "missing field {}", field),
+            ConversionError::InvalidHash(len) => {
+                write!(f, "invalid hash, expected 32 bytes got {}", len)
+            }
+            ConversionError::InvalidBigInt(err) => write!(f, "invalid big int: {}", err),
+            ConversionError::InvalidAccountId(id) => write!(f, "invalid account id {:?}", id),
+            ConversionError::InvalidKey(err) => write!(f, "invalid public key: {}", err),
+            ConversionError::InvalidSignature(err) => write!(f, "invalid signature: {}", err),
+            ConversionError::InvalidEnumValue(name, value) => {
+                write!(f, "invalid {} enum value {}", name, value)
+            }
+            ConversionError::InvalidEncoding(err) => write!(f, "invalid encoding: {}", err),
+            ConversionError::Unrecoverable(what) => {
+                write!(f, "{} cannot be rebuilt from the protobuf model", what)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+fn required<'a, T>(value: &'a Option<T>, field: &'static str) -> Result<&'a T, ConversionError> {
+    value.as_ref().ok_or(ConversionError::MissingField(field))
+}
+
+fn account_id(id: &str) -> Result<AccountId, ConversionError> {
+    id.parse()
+        .map_err(|_| ConversionError::InvalidAccountId(id.to_string()))
+}
+
+fn hash(
+    h: &Option<CryptoHash>,
+    field: &'static str,
+) -> Result<near_primitives::hash::CryptoHash, ConversionError> {
+    near_primitives::hash::CryptoHash::try_from(required(h, field)?)
+}
+
+fn raw_hash(bytes: &[u8]) -> Result<near_primitives::hash::CryptoHash, ConversionError> {
+    <[u8; 32]>::try_from(bytes)
+        .map(near_primitives::hash::CryptoHash)
+        .map_err(|_| ConversionError::InvalidHash(bytes.len()))
+}
+
+fn balance(b: &Option<BigInt>, field: &'static str) -> Result<u128, ConversionError> {
+    u128::try_from(required(b, field)?).map_err(ConversionError::InvalidBigInt)
+}
+
+fn public_key(
+    k: &Option<PublicKey>,
+    field: &'static str,
+) -> Result<NearPublicKey, ConversionError> {
+    NearPublicKey::try_from(required(k, field)?)
+}
+
+fn signature(s: &Option<Signature>, field: &'static str) -> Result<NearSignature, ConversionError> {
+    NearSignature::try_from(required(s, field)?)
+}
+
+impl TryFrom<&Block> for StreamerMessage {
+    type Error = ConversionError;
+
+    /// State changes, kept at the block level, are put back in the shard of their
+    /// `shard_id`.
+    fn try_from(b: &Block) -> Result<Self, Self::Error> {
+        let mut shards = b
+            .shards
+            .iter()
+            .map(near_indexer::IndexerShard::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        for change in &b.state_changes {
+            let shard = shards
+                .iter_mut()
+                .find(|shard| shard.shard_id == change.shard_id)
+                .ok_or(ConversionError::Unrecoverable(
+                    "state change of a removed shard",
+                ))?;
+            shard
+                .state_changes
+                .push(near_views::StateChangeWithCauseView::try_from(change)?);
+        }
+
+        Ok(StreamerMessage {
+            block: near_views::BlockView {
+                author: account_id(&b.author)?,
+                header: near_views::BlockHeaderView::try_from(required(&b.header, "header")?)?,
+                chunks: b
+                    .chunk_headers
+                    .iter()
+                    .map(near_views::ChunkHeaderView::try_from)
+                    .collect::<Result<_, _>>()?,
+            },
+            shards,
+        })
+    }
+}
+
+impl TryFrom<&BlockHeader> for near_views::BlockHeaderView {
+    type Error = ConversionError;
+
+    /// Approvals are restored in position from `block_approvals`. Blocks produced
+    /// before it existed only keep the approvals that were present, those are restored
+    /// as a dense list without the `None` placeholders nearcore uses. `rent_paid` and
+    /// `validator_reward` are deprecated, nearcore views always report them as 0.
+    fn try_from(h: &BlockHeader) -> Result<Self, Self::Error> {
+        Ok(near_views::BlockHeaderView {
+            height: h.height,
+            prev_height: match h.prev_height {
+                0 => None,
+                ph => Some(ph),
+            },
+            epoch_id: hash(&h.epoch_id, "epoch_id")?,
+            next_epoch_id: hash(&h.next_epoch_id, "next_epoch_id")?,
+            hash: hash(&h.hash, "hash")?,
+            prev_hash: hash(&h.prev_hash, "prev_hash")?,
+            prev_state_root: hash(&h.prev_state_root, "prev_state_root")?,
+            block_body_hash: h
+                .block_body_hash
+                .as_ref()
+                .map(near_primitives::hash::CryptoHash::try_from)
+                .transpose()?,
+            chunk_receipts_root: hash(&h.chunk_receipts_root, "chunk_receipts_root")?,
+            chunk_headers_root: hash(&h.chunk_headers_root, "chunk_headers_root")?,
+            chunk_tx_root: hash(&h.chunk_tx_root, "chunk_tx_root")?,
+            outcome_root: hash(&h.outcome_root, "outcome_root")?,
+            chunks_included: h.chunks_included,
+            challenges_root: hash(&h.challenges_root, "challenges_root")?,
+            timestamp: h.timestamp,
+            timestamp_nanosec: h.timestamp_nanosec,
+            random_value: hash(&h.random_value, "random_value")?,
+            validator_proposals: h
+                .validator_proposals
+                .iter()
+                .map(near_views::validator_stake_view::ValidatorStakeView::try_from)
+                .collect::<Result<_, _>>()?,
+            chunk_mask: h.chunk_mask.clone(),
+            gas_price: balance(&h.gas_price, "gas_price")?,
+            block_ordinal: match h.block_ordinal {
+                0 => None,
+                o => Some(o),
+            },
+            rent_paid: 0,
+            validator_reward: 0,
+            total_supply: balance(&h.total_supply, "total_supply")?,
+            challenges_result: h
+                .challenges_result
+                .iter()
+                .map(near_primitives::challenge::SlashedValidator::try_from)
+                .collect::<Result<_, _>>()?,
+            last_final_block: hash(&h.last_final_block, "last_final_block")?,
+            last_ds_final_block: hash(&h.last_ds_final_block, "last_ds_final_block")?,
+            next_bp_hash: hash(&h.next_bp_hash, "next_bp_hash")?,
+            block_merkle_root: hash(&h.block_merkle_root, "block_merkle_root")?,
+            epoch_sync_data_hash: match h.epoch_sync_data_hash.len() {
+                0 => None,
+                _ => Some(raw_hash(&h.epoch_sync_data_hash)?),
+            },
+            approvals: match h.block_approvals.is_empty() {
+                true => h
+                    .approvals
+                    .iter()
+                    .map(|s| NearSignature::try_from(s).map(|sig| Some(Box::new(sig))))
+                    .collect::<Result<_, _>>()?,
+                false => h
+                    .block_approvals
+                    .iter()
+                    .map(|a| {
+                        a.signature
+                            .as_ref()
+                            .map(|s| NearSignature::try_from(s).map(Box::new))
+                            .transpose()
+                    })
+                    .collect::<Result<_, _>>()?,
+            },
+            signature: signature(&h.signature, "signature")?,
+            latest_protocol_version: h.latest_protocol_version,
+            chunk_endorsements: h.chunk_endorsements.as_ref().map(|e| e.bitmaps.clone()),
+        })
+    }
+}
+
+impl TryFrom<&ChunkHeader> for near_views::ChunkHeaderView {
+    type Error = ConversionError;
+
+    /// `rent_paid` is deprecated, nearcore views always report it as 0.
+    fn try_from(ch: &ChunkHeader) -> Result<Self, Self::Error> {
+        Ok(near_views::ChunkHeaderView {
+            chunk_hash: raw_hash(&ch.chunk_hash)?,
+            prev_block_hash: raw_hash(&ch.prev_block_hash)?,
+            outcome_root: raw_hash(&ch.outcome_root)?,
+            prev_state_root: raw_hash(&ch.prev_state_root)?,
+            encoded_merkle_root: raw_hash(&ch.encoded_merkle_root)?,
+            encoded_length: ch.encoded_length,
+            height_created: ch.height_created,
+            height_included: ch.height_included,
+            shard_id: ch.shard_id,
+            gas_used: ch.gas_used,
+            gas_limit: ch.gas_limit,
+            rent_paid: 0,
+            validator_reward: balance(&ch.validator_reward, "validator_reward")?,
+            balance_burnt: balance(&ch.balance_burnt, "balance_burnt")?,
+            outgoing_receipts_root: raw_hash(&ch.outgoing_receipts_root)?,
+            tx_root: raw_hash(&ch.tx_root)?,
+            validator_proposals: ch
+                .validator_proposals
+                .iter()
+                .map(near_views::validator_stake_view::ValidatorStakeView::try_from)
+                .collect::<Result<_, _>>()?,
+            congestion_info: match &ch.congestion_info {
+                None => None,
+                Some(info) => Some(near_views::CongestionInfoView {
+                    delayed_receipts_gas: balance(
+                        &info.delayed_receipts_gas,
+                        "congestion_info.delayed_receipts_gas",
+                    )?,
+                    buffered_receipts_gas: balance(
+                        &info.buffered_receipts_gas,
+                        "congestion_info.buffered_receipts_gas",
+                    )?,
+                    receipt_bytes: info.receipt_bytes,
+                    allowed_shard: u16::try_from(info.allowed_shard).map_err(|_| {
+                        ConversionError::InvalidEncoding(format!(
+                            "allowed shard {} out of range",
+                            info.allowed_shard
+                        ))
+                    })?,
+                }),
+            },
+            signature: signature(&ch.signature, "signature")?,
+        })
+    }
+}
+
+impl TryFrom<&IndexerShard> for near_indexer::IndexerShard {
+    type Error = ConversionError;
+
+    /// Shards are rebuilt without state changes, `TryFrom<&Block>` adds them.
+    fn try_from(is: &IndexerShard) -> Result<Self, Self::Error> {
+        Ok(near_indexer::IndexerShard {
+            shard_id: is.shard_id,
+            chunk: match &is.chunk {
+                None => None,
+                Some(c) => Some(near_indexer::IndexerChunkView::try_from(c)?),
+            },
+            receipt_execution_outcomes: is
+                .receipt_execution_outcomes
+                .iter()
+                .map(near_indexer::IndexerExecutionOutcomeWithReceipt::try_from)
+                .collect::<Result<_, _>>()?,
+            state_changes: vec![],
+        })
+    }
+}
+
+impl TryFrom<&StateChangeWithCause> for near_views::StateChangeWithCauseView {
+    type Error = ConversionError;
+
+    fn try_from(change: &StateChangeWithCause) -> Result<Self, Self::Error> {
+        Ok(near_views::StateChangeWithCauseView {
+            cause: near_views::StateChangeCauseView::try_from(required(
+                &change.cause,
+                "state_change.cause",
+            )?)?,
+            value: near_views::StateChangeValueView::try_from(required(
+                &change.value,
+                "state_change.value",
+            )?)?,
+        })
+    }
+}
+
+impl TryFrom<&StateChangeCause> for near_views::StateChangeCauseView {
+    type Error = ConversionError;
+
+    /// Resharding causes have no counterpart in the schema, they are the only ones
+    /// written without a cause.
+    fn try_from(cause: &StateChangeCause) -> Result<Self, Self::Error> {
+        use near_views::StateChangeCauseView as View;
+        use state_change_cause::Cause;
+
+        Ok(match &cause.cause {
+            None => View::ReshardingV2,
+            Some(Cause::NotWritableToDisk(_)) => View::NotWritableToDisk,
+            Some(Cause::InitialState(_)) => View::InitialState,
+            Some(Cause::TransactionProcessing(c)) => View::TransactionProcessing {
+                tx_hash: hash(&c.tx_hash, "cause.tx_hash")?,
+            },
+            Some(Cause::ActionReceiptProcessingStarted(c)) => {
+                View::ActionReceiptProcessingStarted {
+                    receipt_hash: hash(&c.receipt_hash, "cause.receipt_hash")?,
+                }
+            }
+            Some(Cause::ActionReceiptGasReward(c)) => View::ActionReceiptGasReward {
+                receipt_hash: hash(&c.tx_hash, "cause.tx_hash")?,
+            },
+            Some(Cause::ReceiptProcessing(c)) => View::ReceiptProcessing {
+                receipt_hash: hash(&c.tx_hash, "cause.tx_hash")?,
+            },
+            Some(Cause::PostponedReceipt(c)) => View::PostponedReceipt {
+                receipt_hash: hash(&c.tx_hash, "cause.tx_hash")?,
+            },
+            Some(Cause::UpdatedDelayedReceipts(_)) => View::UpdatedDelayedReceipts,
+            Some(Cause::ValidatorAccountsUpdate(_)) => View::ValidatorAccountsUpdate,
+            Some(Cause::Migration(_)) => View::Migration,
+        })
+    }
+}
+
+impl TryFrom<&StateChangeValue> for near_views::StateChangeValueView {
+    type Error = ConversionError;
+
+    fn try_from(value: &StateChangeValue) -> Result<Self, Self::Error> {
+        use near_views::StateChangeValueView as View;
+        use state_change_value::Value;
+
+        Ok(match required(&value.value, "state_change.value")? {
+            Value::AccountUpdate(v) => View::AccountUpdate {
+                account_id: account_id(&v.account_id)?,
+                account: near_views::AccountView::try_from(required(
+                    &v.account,
+                    "account_update.account",
+                )?)?,
+            },
+            Value::AccountDeletion(v) => View::AccountDeletion {
+                account_id: account_id(&v.account_id)?,
+            },
+            Value::AccessKeyUpdate(v) => View::AccessKeyUpdate {
+                account_id: account_id(&v.account_id)?,
+                public_key: public_key(&v.public_key, "access_key_update.public_key")?,
+                access_key: near_views::AccessKeyView::try_from(required(
+                    &v.access_key,
+                    "access_key_update.access_key",
+                )?)?,
+            },
+            Value::AccessKeyDeletion(v) => View::AccessKeyDeletion {
+                account_id: account_id(&v.account_id)?,
+                public_key: public_key(&v.public_key, "access_key_deletion.public_key")?,
+            },
+            Value::DataUpdate(v) => View::DataUpdate {
+                account_id: account_id(&v.account_id)?,
+                key: v.key.clone().into(),
+                value: v.value.clone().into(),
+            },
+            Value::DataDeletion(v) => View::DataDeletion {
+                account_id: account_id(&v.account_id)?,
+                key: v.key.clone().into(),
+            },
+            Value::ContractCodeUpdate(v) => {
+                if !v.code_hash.is_empty() {
+                    return Err(ConversionError::Unrecoverable("stored contract code"));
+                }
+                View::ContractCodeUpdate {
+                    account_id: account_id(&v.account_id)?,
+                    code: v.code.clone(),
+                }
+            }
+            Value::ContractDeletion(v) => View::ContractCodeDeletion {
+                account_id: account_id(&v.account_id)?,
+            },
+        })
+    }
+}
+
+impl TryFrom<&Account> for near_views::AccountView {
+    type Error = ConversionError;
+
+    /// `storage_paid_at` is deprecated, nearcore views always report it as 0.
+    fn try_from(a: &Account) -> Result<Self, Self::Error> {
+        Ok(near_views::AccountView {
+            amount: balance(&a.amount, "account.amount")?,
+            locked: balance(&a.locked, "account.locked")?,
+            code_hash: hash(&a.code_hash, "account.code_hash")?,
+            storage_usage: a.storage_usage,
+            storage_paid_at: 0,
+        })
+    }
+}
+
+impl TryFrom<&IndexerChunk> for near_indexer::IndexerChunkView {
+    type Error = ConversionError;
+
+    fn try_from(c: &IndexerChunk) -> Result<Self, Self::Error> {
+        Ok(near_indexer::IndexerChunkView {
+            author: account_id(&c.author)?,
+            header: near_views::ChunkHeaderView::try_from(required(&c.header, "chunk.header")?)?,
+            transactions: c
+                .transactions
+                .iter()
+                .map(near_indexer::IndexerTransactionWithOutcome::try_from)
+                .collect::<Result<_, _>>()?,
+            receipts: c
+                .receipts
+                .iter()
+                .map(near_views::ReceiptView::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<&IndexerTransactionWithOutcome> for near_indexer::IndexerTransactionWithOutcome {
+    type Error = ConversionError;
+
+    fn try_from(tx: &IndexerTransactionWithOutcome) -> Result<Self, Self::Error> {
+        let outcome = required(&tx.outcome, "transaction.outcome")?;
+
+        Ok(near_indexer::IndexerTransactionWithOutcome {
+            transaction: near_views::SignedTransactionView::try_from(required(
+                &tx.transaction,
+                "transaction",
+            )?)?,
+            outcome: near_indexer::IndexerExecutionOutcomeWithOptionalReceipt {
+                execution_outcome: near_views::ExecutionOutcomeWithIdView::try_from(required(
+                    &outcome.execution_outcome,
+                    "outcome.execution_outcome",
+                )?)?,
+                receipt: match &outcome.receipt {
+                    None => None,
+                    Some(r) => Some(near_views::ReceiptView::try_from(r)?),
+                },
+            },
+        })
+    }
+}
+
+impl TryFrom<&IndexerExecutionOutcomeWithReceipt>
+    for near_indexer::IndexerExecutionOutcomeWithReceipt
+{
+    type Error = ConversionError;
+
+    fn try_from(r: &IndexerExecutionOutcomeWithReceipt) -> Result<Self, Self::Error> {
+        Ok(near_indexer::IndexerExecutionOutcomeWithReceipt {
+            execution_outcome: near_views::ExecutionOutcomeWithIdView::try_from(required(
+                &r.execution_outcome,
+                "execution_outcome",
+            )?)?,
+            receipt: near_views::ReceiptView::try_from(required(&r.receipt, "receipt")?)?,
+        })
+    }
+}
+
+impl TryFrom<&SignedTransaction> for near_views::SignedTransactionView {
+    type Error = ConversionError;
+
+    fn try_from(tx: &SignedTransaction) -> Result<Self, Self::Error> {
+        Ok(near_views::SignedTransactionView {
+            signer_id: account_id(&tx.signer_id)?,
+            public_key: public_key(&tx.public_key, "transaction.public_key")?,
+            nonce: tx.nonce,
+            receiver_id: account_id(&tx.receiver_id)?,
+            priority_fee: tx.priority_fee,
+            actions: tx
+                .actions
+                .iter()
+                .map(near_views::ActionView::try_from)
+                .collect::<Result<_, _>>()?,
+            signature: signature(&tx.signature, "transaction.signature")?,
+            hash: hash(&tx.hash, "transaction.hash")?,
+        })
+    }
+}
+
+impl TryFrom<&Receipt> for near_views::ReceiptView {
+    type Error = ConversionError;
+
+    fn try_from(r: &Receipt) -> Result<Self, Self::Error> {
+        Ok(near_views::ReceiptView {
+            predecessor_id: account_id(&r.predecessor_id)?,
+            receiver_id: account_id(&r.receiver_id)?,
+            receipt_id: hash(&r.receipt_id, "receipt.receipt_id")?,
+            receipt: match required(&r.receipt, "receipt.receipt")? {
+                receipt::Receipt::Action(a) => ReceiptEnumView::Action {
+                    signer_id: account_id(&a.signer_id)?,
+                    signer_public_key: public_key(
+                        &a.signer_public_key,
+                        "receipt.signer_public_key",
+                    )?,
+                    gas_price: balance(&a.gas_price, "receipt.gas_price")?,
+                    output_data_receivers: a
+                        .output_data_receivers
+                        .iter()
+                        .map(DataReceiverView::try_from)
+                        .collect::<Result<_, _>>()?,
+                    input_data_ids: a
+                        .input_data_ids
+                        .iter()
+                        .map(near_primitives::hash::CryptoHash::try_from)
+                        .collect::<Result<_, _>>()?,
+                    actions: a
+                        .actions
+                        .iter()
+                        .map(near_views::ActionView::try_from)
+                        .collect::<Result<_, _>>()?,
+                    is_promise_yield: a.is_promise_yield,
+                },
+                receipt::Receipt::Data(d) => {
+                    if d.data_trimmed.is_some() {
+                        return Err(ConversionError::Unrecoverable("trimmed receipt data"));
+                    }
+
+                    ReceiptEnumView::Data {
+                        data_id: hash(&d.data_id, "receipt.data_id")?,
+                        data: d.data.clone(),
+                        is_promise_resume: d.is_promise_resume,
+                    }
+                }
+            },
+            priority: r.priority,
+        })
+    }
+}
+
+impl TryFrom<&DataReceiver> for near_views::DataReceiverView {
+    type Error = ConversionError;
+
+    fn try_from(d: &DataReceiver) -> Result<Self, Self::Error> {
+        Ok(DataReceiverView {
+            data_id: hash(&d.data_id, "data_receiver.data_id")?,
+            receiver_id: account_id(&d.receiver_id)?,
+        })
+    }
+}
+
+impl TryFrom<&ExecutionOutcomeWithId> for near_views::ExecutionOutcomeWithIdView {
+    type Error = ConversionError;
+
+    fn try_from(o: &ExecutionOutcomeWithId) -> Result<Self, Self::Error> {
+        Ok(near_views::ExecutionOutcomeWithIdView {
+            proof: near_primitives::merkle::MerklePath::try_from(required(
+                &o.proof,
+                "outcome.proof",
+            )?)?,
+            block_hash: hash(&o.block_hash, "outcome.block_hash")?,
+            id: hash(&o.id, "outcome.id")?,
+            outcome: near_views::ExecutionOutcomeView::try_from(required(
+                &o.outcome,
+                "outcome.outcome",
+            )?)?,
+        })
+    }
+}
+
+impl TryFrom<&ExecutionOutcome> for near_views::ExecutionOutcomeView {
+    type Error = ConversionError;
+
+    fn try_from(o: &ExecutionOutcome) -> Result<Self, Self::Error> {
+        if !o.trimmed_logs.is_empty() {
+            return Err(ConversionError::Unrecoverable("trimmed logs"));
+        }
+        let executor_id = account_id(&o.executor_id)?;
+
+        Ok(near_views::ExecutionOutcomeView {
+            logs: o.logs.clone(),
+            receipt_ids: o
+                .receipt_ids
+                .iter()
+                .map(near_primitives::hash::CryptoHash::try_from)
+                .collect::<Result<_, _>>()?,
+            gas_burnt: o.gas_burnt,
+            tokens_burnt: balance(&o.tokens_burnt, "outcome.tokens_burnt")?,
+            status: execution_status_view(required(&o.status, "outcome.status")?, &executor_id)?,
+            executor_id,
+            metadata: match ExecutionMetadata::from_i32(o.metadata) {
+                Some(ExecutionMetadata::V1) => ExecutionMetadataView {
+                    version: 1,
+                    gas_profile: None,
+                },
+                Some(metadata) => ExecutionMetadataView {
+                    version: match metadata {
+                        ExecutionMetadata::V2 => 2,
+                        _ => 3,
+                    },
+                    gas_profile: Some(
+                        o.gas_profile
+                            .iter()
+                            .map(|cost| near_views::CostGasUsed {
+                                cost_category: cost.cost_category.clone(),
+                                cost: cost.cost.clone(),
+                                gas_used: cost.gas_used,
+                            })
+                            .collect(),
+                    ),
+                },
+                None => {
+                    return Err(ConversionError::InvalidEnumValue(
+                        "ExecutionMetadata",
+                        o.metadata,
+                    ))
+                }
+            },
+        })
+    }
+}
+
+/// Function call, receipt validation, access key and invalid transaction errors are
+/// rebuilt from their JSON-RPC detail. Blocks written before it was kept only have their
+/// kind, those carrying a payload are reported as unrecoverable.
+fn execution_status_view(
+    s: &execution_outcome::Status,
+    executor_id: &AccountId,
+) -> Result<ExecutionStatusView, ConversionError> {
+    Ok(match s {
+        execution_outcome::Status::Unknown(_) => ExecutionStatusView::Unknown,
+        execution_outcome::Status::SuccessValue(v) => {
+            if v.value_trimmed.is_some() {
+                return Err(ConversionError::Unrecoverable("trimmed success value"));
+            }
+            ExecutionStatusView::SuccessValue(v.value.clone())
+        }
+        execution_outcome::Status::SuccessReceiptId(v) => {
+            ExecutionStatusView::SuccessReceiptId(hash(&v.id, "status.id")?)
+        }
+        execution_outcome::Status::Failure(f) => {
+            ExecutionStatusView::Failure(match required(&f.failure, "status.failure")? {
+                failure_execution_status::Failure::ActionError(ae) => {
+                    near_errors::TxExecutionError::ActionError(near_errors::ActionError {
+                        index: ae.index,
+                        kind: action_error_kind(
+                            required(&ae.kind, "action_error.kind")?,
+                            executor_id,
+                        )?,
+                    })
+                }
+                failure_execution_status::Failure::InvalidTxError(e) => {
+                    near_errors::TxExecutionError::InvalidTxError(invalid_tx_error(
+                        *e,
+                        &f.invalid_tx_error_detail,
+                    )?)
+                }
+            })
+        }
+    })
+}
+
+fn action_error_kind(
+    kind: &action_error::Kind,
+    executor_id: &AccountId,
+) -> Result<ActionErrorKind, ConversionError> {
+    Ok(match kind {
+        action_error::Kind::AccountAlreadyExist(k) => ActionErrorKind::AccountAlreadyExists {
+            account_id: account_id(&k.account_id)?,
+        },
+        action_error::Kind::AccountDoesNotExist(k) => ActionErrorKind::AccountDoesNotExist {
+            account_id: account_id(&k.account_id)?,
+        },
+        action_error::Kind::CreateAccountOnlyByRegistrar(k) => {
+            ActionErrorKind::CreateAccountOnlyByRegistrar {
+                account_id: account_id(&k.account_id)?,
+                registrar_account_id: account_id(&k.registrar_account_id)?,
+                predecessor_id: account_id(&k.predecessor_id)?,
+            }
+        }
+        action_error::Kind::CreateAccountNotAllowed(k) => {
+            ActionErrorKind::CreateAccountNotAllowed {
+                account_id: account_id(&k.account_id)?,
+                predecessor_id: account_id(&k.predecessor_id)?,
+            }
+        }
+        action_error::Kind::ActorNoPermission(k) => ActionErrorKind::ActorNoPermission {
+            account_id: account_id(&k.account_id)?,
+            actor_id: account_id(&k.actor_id)?,
+        },
+        action_error::Kind::DeleteKeyDoesNotExist(k) => ActionErrorKind::DeleteKeyDoesNotExist {
+            account_id: account_id(&k.account_id)?,
+            public_key: Box::new(public_key(&k.public_key, "error.public_key")?),
+        },
+        action_error::Kind::AddKeyAlreadyExists(k) => ActionErrorKind::AddKeyAlreadyExists {
+            account_id: account_id(&k.account_id)?,
+            public_key: Box::new(public_key(&k.public_key, "error.public_key")?),
+        },
+        // The forward conversion drops the account, it is always the executor.
+        action_error::Kind::DeleteAccountStaking(_) => ActionErrorKind::DeleteAccountStaking {
+            account_id: executor_id.clone(),
+        },
+        action_error::Kind::LackBalanceForState(k) => ActionErrorKind::LackBalanceForState {
+            account_id: account_id(&k.account_id)?,
+            amount: balance(&k.balance, "error.balance")?,
+        },
+        action_error::Kind::TriesToUnstake(k) => ActionErrorKind::TriesToUnstake {
+            account_id: account_id(&k.account_id)?,
+        },
+        action_error::Kind::TriesToStake(k) => ActionErrorKind::TriesToStake {
+            account_id: account_id(&k.account_id)?,
+            stake: balance(&k.stake, "error.stake")?,
+            locked: balance(&k.locked, "error.locked")?,
+            balance: balance(&k.balance, "error.balance")?,
+        },
+        action_error::Kind::InsufficientStake(k) => ActionErrorKind::InsufficientStake {
+            account_id: account_id(&k.account_id)?,
+            stake: balance(&k.stake, "error.stake")?,
+            minimum_stake: balance(&k.minimum_stake, "error.minimum_stake")?,
+        },
+        action_error::Kind::FunctionCall(k) if !k.detail.is_empty() => {
+            ActionErrorKind::FunctionCallError(from_detail(&k.detail)?)
+        }
+        action_error::Kind::FunctionCall(k) => {
+            ActionErrorKind::FunctionCallError(match FunctionCallErrorSer::from_i32(k.error) {
+                Some(FunctionCallErrorSer::WasmUnknownError) => {
+                    near_errors::FunctionCallError::WasmUnknownError
+                }
+                Some(FunctionCallErrorSer::EvmError) => near_errors::FunctionCallError::_EVMError,
+                Some(_) => return Err(ConversionError::Unrecoverable("function call error")),
+                None => {
+                    return Err(ConversionError::InvalidEnumValue(
+                        "FunctionCallErrorSer",
+                        k.error,
+                    ))
+                }
+            })
+        }
+        action_error::Kind::NewReceiptValidation(k) if !k.detail.is_empty() => {
+            ActionErrorKind::NewReceiptValidationError(from_detail(&k.detail)?)
+        }
+        action_error::Kind::NewReceiptValidation(_) => {
+            return Err(ConversionError::Unrecoverable("receipt validation error"))
+        }
+        action_error::Kind::OnlyImplicitAccountCreationAllowed(k) => {
+            ActionErrorKind::OnlyImplicitAccountCreationAllowed {
+                account_id: account_id(&k.account_id)?,
+            }
+        }
+        action_error::Kind::DeleteAccountWithLargeState(k) => {
+            ActionErrorKind::DeleteAccountWithLargeState {
+                account_id: account_id(&k.account_id)?,
+            }
+        }
+        action_error::Kind::DelegateActionInvalidSignature(_) => {
+            ActionErrorKind::DelegateActionInvalidSignature
+        }
+        action_error::Kind::DelegateActionSenderDoesNotMatchTxReceiver(k) => {
+            ActionErrorKind::DelegateActionSenderDoesNotMatchTxReceiver {
+                sender_id: account_id(&k.sender_id)?,
+                receiver_id: account_id(&k.receiver_id)?,
+            }
+        }
+        action_error::Kind::DelegateActionExpired(_) => ActionErrorKind::DelegateActionExpired,
+        action_error::Kind::DelegateActionAccessKeyError(k) if !k.detail.is_empty() => {
+            ActionErrorKind::DelegateActionAccessKeyError(from_detail(&k.detail)?)
+        }
+        action_error::Kind::DelegateActionAccessKeyError(_) => {
+            return Err(ConversionError::Unrecoverable(
+                "delegate action access key error",
+            ))
+        }
+        action_error::Kind::DelegateActionInvalidNonce(k) => {
+            ActionErrorKind::DelegateActionInvalidNonce {
+                delegate_nonce: k.delegate_nonce,
+                ak_nonce: k.ak_nonce,
+            }
+        }
+        action_error::Kind::DelegateActionNonceTooLarge(k) => {
+            ActionErrorKind::DelegateActionNonceTooLarge {
+                delegate_nonce: k.delegate_nonce,
+                upper_bound: k.upper_bound,
+            }
+        }
+        action_error::Kind::NonRefundableTransferToExistingAccount(k) => {
+            ActionErrorKind::NonRefundableTransferToExistingAccount {
+                account_id: account_id(&k.account_id)?,
+            }
+        }
+    })
+}
+
+fn invalid_tx_error(e: i32, detail: &str) -> Result<near_errors::InvalidTxError, ConversionError> {
+    if !detail.is_empty() {
+        return from_detail(detail);
+    }
+
+    match InvalidTxError::from_i32(e) {
+        Some(InvalidTxError::InvalidSignature) => Ok(near_errors::InvalidTxError::InvalidSignature),
+        Some(InvalidTxError::CostOverflow) => Ok(near_errors::InvalidTxError::CostOverflow),
+        Some(InvalidTxError::InvalidChain) => Ok(near_errors::InvalidTxError::InvalidChain),
+        Some(InvalidTxError::Expired) => Ok(near_errors::InvalidTxError::Expired),
+        Some(_) => Err(ConversionError::Unrecoverable("invalid transaction error")),
+        None => Err(ConversionError::InvalidEnumValue("InvalidTxError", e)),
+    }
+}
+
+/// Parses the JSON-RPC representation of a nearcore error.
+fn from_detail<T: serde::de::DeserializeOwned>(detail: &str) -> Result<T, ConversionError> {
+    serde_json::from_str(detail).map_err(|e| ConversionError::InvalidEncoding(e.to_string()))
+}
+
+impl TryFrom<&MerklePath> for near_primitives::merkle::MerklePath {
+    type Error = ConversionError;
+
+    fn try_from(p: &MerklePath) -> Result<Self, Self::Error> {
+        p.path
+            .iter()
+            .map(|item| {
+                Ok(near_primitives::merkle::MerklePathItem {
+                    hash: hash(&item.hash, "merkle_path.hash")?,
+                    direction: match Direction::from_i32(item.direction) {
+                        Some(Direction::Left) => near_primitives::merkle::Direction::Left,
+                        Some(Direction::Right) => near_primitives::merkle::Direction::Right,
+                        None => {
+                            return Err(ConversionError::InvalidEnumValue(
+                                "Direction",
+                                item.direction,
+                            ))
+                        }
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<&Action> for near_views::ActionView {
+    type Error = ConversionError;
+
+    fn try_from(a: &Action) -> Result<Self, Self::Error> {
+        Ok(match required(&a.action, "action")? {
+            action::Action::CreateAccount(_) => near_views::ActionView::CreateAccount,
+            action::Action::DeployContract(d) => {
+                if !d.code_hash.is_empty() {
+                    return Err(ConversionError::Unrecoverable("stored contract code"));
+                }
+                near_views::ActionView::DeployContract {
+                    code: d.code.clone(),
+                }
+            }
+            action::Action::FunctionCall(f) => {
+                if f.args_trimmed.is_some() {
+                    return Err(ConversionError::Unrecoverable("trimmed function call args"));
+                }
+                near_views::ActionView::FunctionCall {
+                    method_name: f.method_name.clone(),
+                    args: f.args.clone().into(),
+                    gas: f.gas,
+                    deposit: balance(&f.deposit, "function_call.deposit")?,
+                }
+            }
+            action::Action::Transfer(t) => near_views::ActionView::Transfer {
+                deposit: balance(&t.deposit, "transfer.deposit")?,
+            },
+            action::Action::Stake(s) => near_views::ActionView::Stake {
+                stake: balance(&s.stake, "stake.stake")?,
+                public_key: public_key(&s.public_key, "stake.public_key")?,
+            },
+            action::Action::AddKey(k) => near_views::ActionView::AddKey {
+                public_key: public_key(&k.public_key, "add_key.public_key")?,
+                access_key: near_views::AccessKeyView::try_from(required(
+                    &k.access_key,
+                    "add_key.access_key",
+                )?)?,
+            },
+            action::Action::DeleteKey(k) => near_views::ActionView::DeleteKey {
+                public_key: public_key(&k.public_key, "delete_key.public_key")?,
+            },
+            action::Action::DeleteAccount(d) => near_views::ActionView::DeleteAccount {
+                beneficiary_id: account_id(&d.beneficiary_id)?,
+            },
+            action::Action::Delegate(d) => {
+                let delegate_action = required(&d.delegate_action, "delegate.delegate_action")?;
+
+                near_views::ActionView::Delegate {
+                    delegate_action: near_primitives::action::delegate::DelegateAction {
+                        sender_id: account_id(&delegate_action.sender_id)?,
+                        receiver_id: account_id(&delegate_action.receiver_id)?,
+                        actions: delegate_action
+                            .actions
+                            .iter()
+                            .map(near_primitives::action::delegate::NonDelegateAction::try_from)
+                            .collect::<Result<_, _>>()?,
+                        nonce: delegate_action.nonce,
+                        max_block_height: delegate_action.max_block_height,
+                        public_key: public_key(&delegate_action.public_key, "delegate.public_key")?,
+                    },
+                    signature: signature(&d.signature, "delegate.signature")?,
+                }
+            }
+        })
+    }
+}
+
+impl TryFrom<&Action> for near_primitives::action::delegate::NonDelegateAction {
+    type Error = ConversionError;
+
+    fn try_from(a: &Action) -> Result<Self, Self::Error> {
+        let view = near_views::ActionView::try_from(a)?;
+        let near_act = near_primitives::transaction::Action::try_from(view)
+            .map_err(|_| ConversionError::Unrecoverable("delegated action"))?;
+
+        near_primitives::action::delegate::NonDelegateAction::try_from(near_act)
+            .map_err(|_| ConversionError::Unrecoverable("nested delegate action"))
+    }
+}
+
+impl TryFrom<&AccessKey> for near_views::AccessKeyView {
+    type Error = ConversionError;
+
+    fn try_from(k: &AccessKey) -> Result<Self, Self::Error> {
+        Ok(near_views::AccessKeyView {
+            nonce: k.nonce,
+            permission: near_views::AccessKeyPermissionView::try_from(required(
+                &k.permission,
+                "access_key.permission",
+            )?)?,
+        })
+    }
+}
+
+impl TryFrom<&AccessKeyPermission> for near_views::AccessKeyPermissionView {
+    type Error = ConversionError;
+
+    fn try_from(p: &AccessKeyPermission) -> Result<Self, Self::Error> {
+        Ok(match required(&p.permission, "permission")? {
+            access_key_permission::Permission::FunctionCall(f) => {
+                near_views::AccessKeyPermissionView::FunctionCall {
+                    allowance: match &f.allowance {
+                        None => None,
+                        Some(_) => Some(balance(&f.allowance, "permission.allowance")?),
+                    },
+                    receiver_id: f.receiver_id.clone(),
+                    method_names: f.method_names.clone(),
+                }
+            }
+            access_key_permission::Permission::FullAccess(_) => {
+                near_views::AccessKeyPermissionView::FullAccess
+            }
+        })
+    }
+}
+
+impl TryFrom<&ValidatorStake> for near_views::validator_stake_view::ValidatorStakeView {
+    type Error = ConversionError;
+
+    fn try_from(sv: &ValidatorStake) -> Result<Self, Self::Error> {
+        Ok(near_views::validator_stake_view::ValidatorStakeView::V1(
+            near_views::validator_stake_view::ValidatorStakeViewV1 {
+                account_id: account_id(&sv.account_id)?,
+                public_key: public_key(&sv.public_key, "validator_stake.public_key")?,
+                stake: balance(&sv.stake, "validator_stake.stake")?,
+            },
+        ))
+    }
+}
+
+impl TryFrom<&SlashedValidator> for near_primitives::challenge::SlashedValidator {
+    type Error = ConversionError;
+
+    fn try_from(sv: &SlashedValidator) -> Result<Self, Self::Error> {
+        Ok(near_primitives::challenge::SlashedValidator {
+            account_id: account_id(&sv.account_id)?,
+            is_double_sign: sv.is_double_sign,
+        })
+    }
+}
+
+impl TryFrom<&Signature> for NearSignature {
+    type Error = ConversionError;
+
+    fn try_from(sig: &Signature) -> Result<Self, Self::Error> {
+        let key_type = match CurveKind::from_i32(sig.r#type) {
+            Some(CurveKind::Ed25519) => KeyType::ED25519,
+            Some(CurveKind::Secp256k1) => KeyType::SECP256K1,
+            None => return Err(ConversionError::InvalidEnumValue("CurveKind", sig.r#type)),
+        };
+
+        NearSignature::from_parts(key_type, &sig.bytes)
+            .map_err(|e| ConversionError::InvalidSignature(e.to_string()))
+    }
+}
+
+impl TryFrom<&PublicKey> for NearPublicKey {
+    type Error = ConversionError;
+
+    fn try_from(key: &PublicKey) -> Result<Self, Self::Error> {
+        match CurveKind::from_i32(key.r#type) {
+            Some(CurveKind::Ed25519) => ED25519PublicKey::try_from(key.bytes.as_slice())
+                .map(NearPublicKey::ED25519)
+                .map_err(|e| ConversionError::InvalidKey(e.to_string())),
+            Some(CurveKind::Secp256k1) => Secp256K1PublicKey::try_from(key.bytes.as_slice())
+                .map(NearPublicKey::SECP256K1)
+                .map_err(|e| ConversionError::InvalidKey(e.to_string())),
+            None => Err(ConversionError::InvalidEnumValue("CurveKind", key.r#type)),
+        }
+    }
+}
+
+impl TryFrom<&CryptoHash> for near_primitives::hash::CryptoHash {
+    type Error = ConversionError;
+
+    fn try_from(h: &CryptoHash) -> Result<Self, Self::Error> {
+        raw_hash(&h.bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_errors::{
+        CompilationError, FunctionCallError, InvalidAccessKeyError, ReceiptValidationError,
+        TxExecutionError,
+    };
+    use quickcheck::{Arbitrary, Gen};
+    use quickcheck_macros::quickcheck;
+
+    const ACCOUNTS: &[&str] = &["alice.near", "bob.near", "system", "aurora", "near"];
+
+    fn arbitrary_account(g: &mut Gen) -> AccountId {
+        g.choose(ACCOUNTS).unwrap().parse().unwrap()
+    }
+
+    fn arbitrary_hash(g: &mut Gen) -> near_primitives::hash::CryptoHash {
+        near_primitives::hash::CryptoHash(std::array::from_fn(|_| u8::arbitrary(g)))
+    }
+
+    fn arbitrary_key(g: &mut Gen) -> NearPublicKey {
+        match bool::arbitrary(g) {
+            true => {
+                let bytes: [u8; 32] = std::array::from_fn(|_| u8::arbitrary(g));
+                NearPublicKey::ED25519(ED25519PublicKey::from(bytes))
+            }
+            false => {
+                let bytes: [u8; 64] = std::array::from_fn(|_| u8::arbitrary(g));
+                NearPublicKey::SECP256K1(Secp256K1PublicKey::from(bytes))
+            }
+        }
+    }
+
+    fn arbitrary_signature(g: &mut Gen) -> NearSignature {
+        let (key_type, len) = match bool::arbitrary(g) {
+            true => (KeyType::ED25519, 64),
+            false => (KeyType::SECP256K1, 65),
+        };
+        let bytes: Vec<u8> = (0..len).map(|_| u8::arbitrary(g)).collect();
+        NearSignature::from_parts(key_type, &bytes).unwrap()
+    }
+
+    fn arbitrary_access_key(g: &mut Gen) -> near_views::AccessKeyView {
+        near_views::AccessKeyView {
+            nonce: u64::arbitrary(g),
+            permission: match bool::arbitrary(g) {
+                true => near_views::AccessKeyPermissionView::FullAccess,
+                false => near_views::AccessKeyPermissionView::FunctionCall {
+                    allowance: Option::<u128>::arbitrary(g),
+                    receiver_id: arbitrary_account(g).to_string(),
+                    method_names: Vec::<String>::arbitrary(g),
+                },
+            },
+        }
+    }
+
+    fn arbitrary_action(g: &mut Gen) -> near_views::ActionView {
+        match u8::arbitrary(g) % 9 {
+            8 => {
+                let actions = (0..u8::arbitrary(g) % 3)
+                    .map(|_| {
+                        let action = near_primitives::transaction::Action::try_from(
+                            arbitrary_non_delegate_action(g),
+                        )
+                        .unwrap();
+                        near_primitives::action::delegate::NonDelegateAction::try_from(action)
+                            .unwrap_or_else(|_| unreachable!())
+                    })
+                    .collect();
+
+                near_views::ActionView::Delegate {
+                    delegate_action: near_primitives::action::delegate::DelegateAction {
+                        sender_id: arbitrary_account(g),
+                        receiver_id: arbitrary_account(g),
+                        actions,
+                        nonce: u64::arbitrary(g),
+                        max_block_height: u64::arbitrary(g),
+                        public_key: arbitrary_key(g),
+                    },
+                    signature: arbitrary_signature(g),
+                }
+            }
+            _ => arbitrary_non_delegate_action(g),
+        }
+    }
+
+    fn arbitrary_non_delegate_action(g: &mut Gen) -> near_views::ActionView {
+        match u8::arbitrary(g) % 8 {
+            5 => near_views::ActionView::Stake {
+                stake: u128::arbitrary(g),
+                public_key: arbitrary_key(g),
+            },
+            6 => near_views::ActionView::AddKey {
+                public_key: arbitrary_key(g),
+                access_key: arbitrary_access_key(g),
+            },
+            7 => near_views::ActionView::DeleteKey {
+                public_key: arbitrary_key(g),
+            },
+            0 => near_views::ActionView::CreateAccount,
+            1 => near_views::ActionView::Transfer {
+                deposit: u128::arbitrary(g),
+            },
+            2 => near_views::ActionView::FunctionCall {
+                method_name: String::arbitrary(g),
+                args: Vec::<u8>::arbitrary(g).into(),
+                gas: u64::arbitrary(g),
+                deposit: u128::arbitrary(g),
+            },
+            3 => near_views::ActionView::DeployContract {
+                code: Vec::<u8>::arbitrary(g),
+            },
+            _ => near_views::ActionView::DeleteAccount {
+                beneficiary_id: arbitrary_account(g),
+            },
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct ArbitraryReceipt(near_views::ReceiptView);
+
+    impl Arbitrary for ArbitraryReceipt {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let receipt = if bool::arbitrary(g) {
+                ReceiptEnumView::Data {
+                    data_id: arbitrary_hash(g),
+                    data: Option::<Vec<u8>>::arbitrary(g),
+                    is_promise_resume: bool::arbitrary(g),
+                }
+            } else {
+                ReceiptEnumView::Action {
+                    signer_id: arbitrary_account(g),
+                    signer_public_key: arbitrary_key(g),
+                    gas_price: u128::arbitrary(g),
+                    output_data_receivers: (0..u8::arbitrary(g) % 3)
+                        .map(|_| DataReceiverView {
+                            data_id: arbitrary_hash(g),
+                            receiver_id: arbitrary_account(g),
+                        })
+                        .collect(),
+                    input_data_ids: (0..u8::arbitrary(g) % 3)
+                        .map(|_| arbitrary_hash(g))
+                        .collect(),
+                    actions: (0..u8::arbitrary(g) % 4)
+                        .map(|_| arbitrary_action(g))
+                        .collect(),
+                    is_promise_yield: bool::arbitrary(g),
+                }
+            };
+
+            ArbitraryReceipt(near_views::ReceiptView {
+                predecessor_id: arbitrary_account(g),
+                receiver_id: arbitrary_account(g),
+                receipt_id: arbitrary_hash(g),
+                receipt,
+                priority: u64::arbitrary(g),
+            })
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct ArbitraryTransaction(near_views::SignedTransactionView);
+
+    impl Arbitrary for ArbitraryTransaction {
+        fn arbitrary(g: &mut Gen) -> Self {
+            ArbitraryTransaction(near_views::SignedTransactionView {
+                signer_id: arbitrary_account(g),
+                public_key: arbitrary_key(g),
+                nonce: u64::arbitrary(g),
+                receiver_id: arbitrary_account(g),
+                priority_fee: u64::arbitrary(g),
+                actions: (0..u8::arbitrary(g) % 4)
+                    .map(|_| arbitrary_action(g))
+                    .collect(),
+                signature: arbitrary_signature(g),
+                hash: arbitrary_hash(g),
+            })
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct ArbitraryOutcome(near_views::ExecutionOutcomeView);
+
+    impl Arbitrary for ArbitraryOutcome {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let status = match u8::arbitrary(g) % 4 {
+                0 => ExecutionStatusView::Unknown,
+                1 => ExecutionStatusView::SuccessValue(Vec::<u8>::arbitrary(g)),
+                2 => ExecutionStatusView::SuccessReceiptId(arbitrary_hash(g)),
+                _ => ExecutionStatusView::Failure(TxExecutionError::ActionError(
+                    near_errors::ActionError {
+                        index: Option::<u64>::arbitrary(g),
+                        kind: ActionErrorKind::AccountDoesNotExist {
+                            account_id: arbitrary_account(g),
+                        },
+                    },
+                )),
+            };
+
+            ArbitraryOutcome(near_views::ExecutionOutcomeView {
+                logs: Vec::<String>::arbitrary(g),
+                receipt_ids: (0..u8::arbitrary(g) % 3)
+                    .map(|_| arbitrary_hash(g))
+                    .collect(),
+                gas_burnt: u64::arbitrary(g),
+                tokens_burnt: u128::arbitrary(g),
+                executor_id: arbitrary_account(g),
+                status,
+                metadata: ExecutionMetadataView {
+                    version: 1,
+                    gas_profile: None,
+                },
+            })
+        }
+    }
+
+    fn arbitrary_stake(g: &mut Gen) -> near_views::validator_stake_view::ValidatorStakeView {
+        near_views::validator_stake_view::ValidatorStakeView::V1(
+            near_views::validator_stake_view::ValidatorStakeViewV1 {
+                account_id: arbitrary_account(g),
+                public_key: arbitrary_key(g),
+                stake: u128::arbitrary(g),
+            },
+        )
+    }
+
+    fn arbitrary_header(g: &mut Gen) -> near_views::BlockHeaderView {
+        let nonzero = |g: &mut Gen| Some(u64::arbitrary(g).max(1)).filter(|_| bool::arbitrary(g));
+
+        near_views::BlockHeaderView {
+            height: u64::arbitrary(g),
+            prev_height: nonzero(g),
+            epoch_id: arbitrary_hash(g),
+            next_epoch_id: arbitrary_hash(g),
+            hash: arbitrary_hash(g),
+            prev_hash: arbitrary_hash(g),
+            prev_state_root: arbitrary_hash(g),
+            block_body_hash: Some(arbitrary_hash(g)).filter(|_| bool::arbitrary(g)),
+            chunk_receipts_root: arbitrary_hash(g),
+            chunk_headers_root: arbitrary_hash(g),
+            chunk_tx_root: arbitrary_hash(g),
+            outcome_root: arbitrary_hash(g),
+            chunks_included: u64::arbitrary(g),
+            challenges_root: arbitrary_hash(g),
+            timestamp: u64::arbitrary(g),
+            timestamp_nanosec: u64::arbitrary(g),
+            random_value: arbitrary_hash(g),
+            validator_proposals: (0..u8::arbitrary(g) % 3)
+                .map(|_| arbitrary_stake(g))
+                .collect(),
+            chunk_mask: Vec::<bool>::arbitrary(g),
+            gas_price: u128::arbitrary(g),
+            block_ordinal: nonzero(g),
+            rent_paid: 0,
+            validator_reward: 0,
+            total_supply: u128::arbitrary(g),
+            challenges_result: (0..u8::arbitrary(g) % 3)
+                .map(|_| near_primitives::challenge::SlashedValidator {
+                    account_id: arbitrary_account(g),
+                    is_double_sign: bool::arbitrary(g),
+                })
+                .collect(),
+            last_final_block: arbitrary_hash(g),
+            last_ds_final_block: arbitrary_hash(g),
+            next_bp_hash: arbitrary_hash(g),
+            block_merkle_root: arbitrary_hash(g),
+            epoch_sync_data_hash: Some(arbitrary_hash(g)).filter(|_| bool::arbitrary(g)),
+            approvals: (0..u8::arbitrary(g) % 5)
+                .map(|_| Some(Box::new(arbitrary_signature(g))).filter(|_| bool::arbitrary(g)))
+                .collect(),
+            signature: arbitrary_signature(g),
+            latest_protocol_version: u32::arbitrary(g),
+            chunk_endorsements: Option::<Vec<Vec<u8>>>::arbitrary(g),
+        }
+    }
+
+    fn arbitrary_chunk_header(g: &mut Gen, shard_id: u64) -> near_views::ChunkHeaderView {
+        near_views::ChunkHeaderView {
+            chunk_hash: arbitrary_hash(g),
+            prev_block_hash: arbitrary_hash(g),
+            outcome_root: arbitrary_hash(g),
+            prev_state_root: arbitrary_hash(g),
+            encoded_merkle_root: arbitrary_hash(g),
+            encoded_length: u64::arbitrary(g),
+            height_created: u64::arbitrary(g),
+            height_included: u64::arbitrary(g),
+            shard_id,
+            gas_used: u64::arbitrary(g),
+            gas_limit: u64::arbitrary(g),
+            rent_paid: 0,
+            validator_reward: u128::arbitrary(g),
+            balance_burnt: u128::arbitrary(g),
+            outgoing_receipts_root: arbitrary_hash(g),
+            tx_root: arbitrary_hash(g),
+            validator_proposals: (0..u8::arbitrary(g) % 3)
+                .map(|_| arbitrary_stake(g))
+                .collect(),
+            congestion_info: match bool::arbitrary(g) {
+                true => Some(near_views::CongestionInfoView {
+                    delayed_receipts_gas: u128::arbitrary(g),
+                    buffered_receipts_gas: u128::arbitrary(g),
+                    receipt_bytes: u64::arbitrary(g),
+                    allowed_shard: u16::arbitrary(g),
+                }),
+                false => None,
+            },
+            signature: arbitrary_signature(g),
+        }
+    }
+
+    fn arbitrary_outcome_with_id(g: &mut Gen) -> near_views::ExecutionOutcomeWithIdView {
+        near_views::ExecutionOutcomeWithIdView {
+            proof: (0..u8::arbitrary(g) % 4)
+                .map(|_| near_primitives::merkle::MerklePathItem {
+                    hash: arbitrary_hash(g),
+                    direction: match bool::arbitrary(g) {
+                        true => near_primitives::merkle::Direction::Left,
+                        false => near_primitives::merkle::Direction::Right,
+                    },
+                })
+                .collect(),
+            block_hash: arbitrary_hash(g),
+            id: arbitrary_hash(g),
+            outcome: ArbitraryOutcome::arbitrary(g).0,
+        }
+    }
+
+    fn arbitrary_state_change(g: &mut Gen) -> near_views::StateChangeWithCauseView {
+        use near_views::{StateChangeCauseView as Cause, StateChangeValueView as Value};
+
+        let cause = match u8::arbitrary(g) % 5 {
+            0 => Cause::TransactionProcessing {
+                tx_hash: arbitrary_hash(g),
+            },
+            1 => Cause::ReceiptProcessing {
+                receipt_hash: arbitrary_hash(g),
+            },
+            2 => Cause::ActionReceiptGasReward {
+                receipt_hash: arbitrary_hash(g),
+            },
+            3 => Cause::ValidatorAccountsUpdate,
+            _ => Cause::InitialState,
+        };
+        let account_id = arbitrary_account(g);
+        let value = match u8::arbitrary(g) % 6 {
+            0 => Value::AccountUpdate {
+                account_id,
+                account: near_views::AccountView {
+                    amount: u128::arbitrary(g),
+                    locked: u128::arbitrary(g),
+                    code_hash: arbitrary_hash(g),
+                    storage_usage: u64::arbitrary(g),
+                    storage_paid_at: 0,
+                },
+            },
+            1 => Value::AccessKeyUpdate {
+                account_id,
+                public_key: arbitrary_key(g),
+                access_key: arbitrary_access_key(g),
+            },
+            2 => Value::AccessKeyDeletion {
+                account_id,
+                public_key: arbitrary_key(g),
+            },
+            3 => Value::DataUpdate {
+                account_id,
+                key: Vec::<u8>::arbitrary(g).into(),
+                value: Vec::<u8>::arbitrary(g).into(),
+            },
+            4 => Value::ContractCodeUpdate {
+                account_id,
+                code: Vec::<u8>::arbitrary(g),
+            },
+            _ => Value::AccountDeletion { account_id },
+        };
+
+        near_views::StateChangeWithCauseView { cause, value }
+    }
+
+    #[derive(Clone, Debug)]
+    struct ArbitraryMessage(StreamerMessage);
+
+    impl Arbitrary for ArbitraryMessage {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let shard_count = u8::arbitrary(g) % 4;
+            let chunks: Vec<_> = (0..shard_count as u64)
+                .map(|shard_id| arbitrary_chunk_header(g, shard_id))
+                .collect();
+            let shards = chunks
+                .iter()
+                .map(|header| near_indexer::IndexerShard {
+                    shard_id: header.shard_id,
+                    chunk: match bool::arbitrary(g) {
+                        true => Some(near_indexer::IndexerChunkView {
+                            author: arbitrary_account(g),
+                            header: header.clone(),
+                            transactions: (0..u8::arbitrary(g) % 3)
+                                .map(|_| near_indexer::IndexerTransactionWithOutcome {
+                                    transaction: ArbitraryTransaction::arbitrary(g).0,
+                                    outcome:
+                                        near_indexer::IndexerExecutionOutcomeWithOptionalReceipt {
+                                            execution_outcome: arbitrary_outcome_with_id(g),
+                                            receipt: Option::<ArbitraryReceipt>::arbitrary(g)
+                                                .map(|receipt| receipt.0),
+                                        },
+                                })
+                                .collect(),
+                            receipts: (0..u8::arbitrary(g) % 3)
+                                .map(|_| ArbitraryReceipt::arbitrary(g).0)
+                                .collect(),
+                        }),
+                        false => None,
+                    },
+                    receipt_execution_outcomes: (0..u8::arbitrary(g) % 3)
+                        .map(|_| near_indexer::IndexerExecutionOutcomeWithReceipt {
+                            execution_outcome: arbitrary_outcome_with_id(g),
+                            receipt: ArbitraryReceipt::arbitrary(g).0,
+                        })
+                        .collect(),
+                    state_changes: (0..u8::arbitrary(g) % 4)
+                        .map(|_| arbitrary_state_change(g))
+                        .collect(),
+                })
+                .collect();
+
+            ArbitraryMessage(StreamerMessage {
+                block: near_views::BlockView {
+                    author: arbitrary_account(g),
+                    header: arbitrary_header(g),
+                    chunks,
+                },
+                shards,
+            })
+        }
+    }
+
+    /// Round trips through the protobuf encoding too, so that everything the
+    /// conversion keeps is also kept on the wire.
+    #[quickcheck]
+    fn message_round_trips(message: ArbitraryMessage) -> bool {
+        let block = Block::from(message.0.clone());
+        let decoded =
+            <Block as prost::Message>::decode(prost::Message::encode_to_vec(&block).as_slice())
+                .unwrap();
+        let rebuilt = StreamerMessage::try_from(&decoded).unwrap();
+
+        serde_json::to_value(&rebuilt).unwrap() == serde_json::to_value(&message.0).unwrap()
+    }
+
+    #[quickcheck]
+    fn borrowed_message_converts_like_owned(message: ArbitraryMessage) -> bool {
+        Block::from(&message.0) == Block::from(message.0)
+    }
+
+    #[quickcheck]
+    fn receipt_round_trips(receipt: ArbitraryReceipt) -> bool {
+        let converted = Receipt::from(receipt.0.clone());
+        near_views::ReceiptView::try_from(&converted) == Ok(receipt.0)
+    }
+
+    #[quickcheck]
+    fn transaction_round_trips(tx: ArbitraryTransaction) -> bool {
+        let converted = SignedTransaction::from(tx.0.clone());
+        near_views::SignedTransactionView::try_from(&converted) == Ok(tx.0)
+    }
+
+    #[quickcheck]
+    fn outcome_round_trips(outcome: ArbitraryOutcome) -> bool {
+        let converted = ExecutionOutcome::from(outcome.0.clone());
+        near_views::ExecutionOutcomeView::try_from(&converted) == Ok(outcome.0)
+    }
+
+    fn data_receipt(data: Option<Vec<u8>>) -> near_views::ReceiptView {
+        near_views::ReceiptView {
+            predecessor_id: "alice.near".parse().unwrap(),
+            receiver_id: "bob.near".parse().unwrap(),
+            receipt_id: near_primitives::hash::CryptoHash::default(),
+            receipt: ReceiptEnumView::Data {
+                data_id: near_primitives::hash::CryptoHash::default(),
+                data,
+                is_promise_resume: false,
+            },
+            priority: 0,
+        }
+    }
+
+    #[test]
+    fn data_receipt_keeps_absent_and_empty_data_apart() {
+        for data in [None, Some(vec![])] {
+            let view = data_receipt(data);
+            let converted = Receipt::from(view.clone());
+            assert_eq!(near_views::ReceiptView::try_from(&converted), Ok(view));
+        }
+    }
+
+    fn failed_outcome(error: TxExecutionError) -> near_views::ExecutionOutcomeView {
+        near_views::ExecutionOutcomeView {
+            logs: vec![],
+            receipt_ids: vec![],
+            gas_burnt: 0,
+            tokens_burnt: 0,
+            executor_id: "alice.near".parse().unwrap(),
+            status: ExecutionStatusView::Failure(error),
+            metadata: ExecutionMetadataView {
+                version: 1,
+                gas_profile: None,
+            },
+        }
+    }
+
+    fn convert_error(error: TxExecutionError) -> Result<(), ConversionError> {
+        let view = failed_outcome(error);
+        let converted = ExecutionOutcome::from(view.clone());
+        let rebuilt = near_views::ExecutionOutcomeView::try_from(&converted)?;
+        assert_eq!(rebuilt, view);
+        Ok(())
+    }
+
+    fn action_error(kind: ActionErrorKind) -> TxExecutionError {
+        TxExecutionError::ActionError(near_errors::ActionError {
+            index: Some(0),
+            kind,
+        })
+    }
+
+    #[test]
+    fn action_error_kinds_round_trip_or_fail() {
+        let account = || "alice.near".parse::<AccountId>().unwrap();
+        let key = || NearPublicKey::empty(KeyType::ED25519);
+
+        let representable = vec![
+            ActionErrorKind::AccountAlreadyExists {
+                account_id: account(),
+            },
+            ActionErrorKind::AccountDoesNotExist {
+                account_id: account(),
+            },
+            ActionErrorKind::CreateAccountOnlyByRegistrar {
+                account_id: account(),
+                registrar_account_id: "registrar".parse().unwrap(),
+                predecessor_id: "bob.near".parse().unwrap(),
+            },
+            ActionErrorKind::CreateAccountNotAllowed {
+                account_id: account(),
+                predecessor_id: "bob.near".parse().unwrap(),
+            },
+            ActionErrorKind::ActorNoPermission {
+                account_id: account(),
+                actor_id: "bob.near".parse().unwrap(),
+            },
+            ActionErrorKind::DeleteKeyDoesNotExist {
+                account_id: account(),
+                public_key: Box::new(key()),
+            },
+            ActionErrorKind::AddKeyAlreadyExists {
+                account_id: account(),
+                public_key: Box::new(key()),
+            },
+            ActionErrorKind::DeleteAccountStaking {
+                account_id: account(),
+            },
+            ActionErrorKind::LackBalanceForState {
+                account_id: account(),
+                amount: 1,
+            },
+            ActionErrorKind::TriesToUnstake {
+                account_id: account(),
+            },
+            ActionErrorKind::TriesToStake {
+                account_id: account(),
+                stake: 1,
+                locked: 2,
+                balance: 3,
+            },
+            ActionErrorKind::InsufficientStake {
+                account_id: account(),
+                stake: 1,
+                minimum_stake: 2,
+            },
+            ActionErrorKind::FunctionCallError(FunctionCallError::WasmUnknownError),
+            ActionErrorKind::FunctionCallError(FunctionCallError::_EVMError),
+            ActionErrorKind::OnlyImplicitAccountCreationAllowed {
+                account_id: account(),
+            },
+            ActionErrorKind::DeleteAccountWithLargeState {
+                account_id: account(),
+            },
+            ActionErrorKind::DelegateActionInvalidSignature,
+            ActionErrorKind::DelegateActionSenderDoesNotMatchTxReceiver {
+                sender_id: account(),
+                receiver_id: "bob.near".parse().unwrap(),
+            },
+            ActionErrorKind::DelegateActionExpired,
+            ActionErrorKind::DelegateActionInvalidNonce {
+                delegate_nonce: 1,
+                ak_nonce: 2,
+            },
+            ActionErrorKind::DelegateActionNonceTooLarge {
+                delegate_nonce: 1,
+                upper_bound: 2,
+            },
+            ActionErrorKind::NonRefundableTransferToExistingAccount {
+                account_id: account(),
+            },
+        ];
+        for kind in representable {
+            assert_eq!(
+                convert_error(action_error(kind.clone())),
+                Ok(()),
+                "{:?}",
+                kind
+            );
+        }
+
+        let with_payload = vec![
+            ActionErrorKind::FunctionCallError(FunctionCallError::CompilationError(
+                CompilationError::CodeDoesNotExist {
+                    account_id: account(),
+                },
+            )),
+            ActionErrorKind::FunctionCallError(FunctionCallError::ExecutionError(
+                "panicked".to_string(),
+            )),
+            ActionErrorKind::NewReceiptValidationError(
+                ReceiptValidationError::InvalidPredecessorId {
+                    account_id: "?".to_string(),
+                },
+            ),
+            ActionErrorKind::DelegateActionAccessKeyError(
+                InvalidAccessKeyError::AccessKeyNotFound {
+                    account_id: account(),
+                    public_key: Box::new(key()),
+                },
+            ),
+        ];
+        for kind in with_payload {
+            let error = action_error(kind.clone());
+            assert_eq!(convert_error(error.clone()), Ok(()), "{:?}", kind);
+            assert!(
+                matches!(
+                    convert_without_detail(error),
+                    Err(ConversionError::Unrecoverable(_))
+                ),
+                "{:?}",
+                kind
+            );
+        }
+    }
+
+    /// Converts an error back as written before error details were kept.
+    fn convert_without_detail(error: TxExecutionError) -> Result<(), ConversionError> {
+        let mut outcome = ExecutionOutcome::from(failed_outcome(error));
+        if let Some(execution_outcome::Status::Failure(f)) = outcome.status.as_mut() {
+            f.invalid_tx_error_detail.clear();
+            if let Some(failure_execution_status::Failure::ActionError(ae)) = f.failure.as_mut() {
+                match ae.kind.as_mut() {
+                    Some(action_error::Kind::FunctionCall(k)) => k.detail.clear(),
+                    Some(action_error::Kind::NewReceiptValidation(k)) => k.detail.clear(),
+                    Some(action_error::Kind::DelegateActionAccessKeyError(k)) => k.detail.clear(),
+                    _ => {}
+                }
+            }
+        }
+        near_views::ExecutionOutcomeView::try_from(&outcome).map(|_| ())
+    }
+
+    #[test]
+    fn invalid_tx_errors_round_trip_or_fail() {
+        let representable = vec![
+            near_errors::InvalidTxError::InvalidSignature,
+            near_errors::InvalidTxError::CostOverflow,
+            near_errors::InvalidTxError::InvalidChain,
+            near_errors::InvalidTxError::Expired,
+        ];
+        for error in representable {
+            let result = convert_error(TxExecutionError::InvalidTxError(error.clone()));
+            assert_eq!(result, Ok(()), "{:?}", error);
+        }
+
+        let with_payload = vec![
+            near_errors::InvalidTxError::InvalidSignerId {
+                signer_id: "?".to_string(),
+            },
+            near_errors::InvalidTxError::InvalidNonce {
+                tx_nonce: 1,
+                ak_nonce: 2,
+            },
+            near_errors::InvalidTxError::NotEnoughBalance {
+                signer_id: "alice.near".parse().unwrap(),
+                balance: 1,
+                cost: 2,
+            },
+        ];
+        for error in with_payload {
+            let error = TxExecutionError::InvalidTxError(error);
+            assert_eq!(convert_error(error.clone()), Ok(()), "{:?}", error);
+            assert!(
+                matches!(
+                    convert_without_detail(error.clone()),
+                    Err(ConversionError::Unrecoverable(_))
+                ),
+                "{:?}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn gas_profile_round_trips() {
+        for version in [2, 3] {
+            let mut view = failed_outcome(action_error(ActionErrorKind::DelegateActionExpired));
+            view.metadata = ExecutionMetadataView {
+                version,
+                gas_profile: Some(vec![
+                    near_views::CostGasUsed {
+                        cost_category: "ACTION_COST".to_string(),
+                        cost: "FUNCTION_CALL_BASE".to_string(),
+                        gas_used: 200_000_000_000,
+                    },
+                    near_views::CostGasUsed {
+                        cost_category: "WASM_HOST_COST".to_string(),
+                        cost: "READ_MEMORY_BYTE".to_string(),
+                        gas_used: 3_000_000,
+                    },
+                ]),
+            };
+
+            let converted = ExecutionOutcome::from(view.clone());
+            assert_eq!(converted.gas_profile.len(), 2);
+            assert_eq!(
+                near_views::ExecutionOutcomeView::try_from(&converted),
+                Ok(view)
+            );
+        }
+    }
+
+    #[test]
+    fn state_changes_round_trip() {
+        use near_views::{StateChangeCauseView as Cause, StateChangeValueView as Value};
+
+        let account = || "alice.near".parse::<AccountId>().unwrap();
+        let key = || NearPublicKey::empty(KeyType::ED25519);
+        let hash = near_primitives::hash::CryptoHash([7; 32]);
+        let changes = vec![
+            (
+                Cause::TransactionProcessing { tx_hash: hash },
+                Value::AccountUpdate {
+                    account_id: account(),
+                    account: near_views::AccountView {
+                        amount: 10,
+                        locked: 2,
+                        code_hash: hash,
+                        storage_usage: 182,
+                        storage_paid_at: 0,
+                    },
+                },
+            ),
+            (
+                Cause::ReceiptProcessing { receipt_hash: hash },
+                Value::AccessKeyUpdate {
+                    account_id: account(),
+                    public_key: key(),
+                    access_key: near_views::AccessKeyView {
+                        nonce: 1,
+                        permission: near_views::AccessKeyPermissionView::FullAccess,
+                    },
+                },
+            ),
+            (
+                Cause::PostponedReceipt { receipt_hash: hash },
+                Value::DataUpdate {
+                    account_id: account(),
+                    key: vec![1, 2].into(),
+                    value: vec![3].into(),
+                },
+            ),
+            (
+                Cause::ValidatorAccountsUpdate,
+                Value::ContractCodeUpdate {
+                    account_id: account(),
+                    code: vec![0, 97, 115, 109],
+                },
+            ),
+            (
+                Cause::ReshardingV2,
+                Value::AccountDeletion {
+                    account_id: account(),
+                },
+            ),
+        ];
+
+        for (cause, value) in changes {
+            let view = near_views::StateChangeWithCauseView { cause, value };
+            let converted = StateChangeWithCause::from(view.clone());
+            let rebuilt = near_views::StateChangeWithCauseView::try_from(&converted).unwrap();
+            assert_eq!(
+                serde_json::to_value(&rebuilt).unwrap(),
+                serde_json::to_value(&view).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn trimmed_payloads_fail_conversion() {
+        let mut receipt = Receipt::from(data_receipt(Some(vec![1; 8])));
+        if let Some(receipt::Receipt::Data(data)) = receipt.receipt.as_mut() {
+            data.data_trimmed = Some(TrimmedPayload::default());
+        }
+
+        assert_eq!(
+            near_views::ReceiptView::try_from(&receipt),
+            Err(ConversionError::Unrecoverable("trimmed receipt data"))
+        );
+    }
+}
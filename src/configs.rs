@@ -1,3 +1,8 @@
+use crate::args::AbiFile;
+use crate::codec::HashEncoding;
+use crate::continuity::{ContinuityPolicy, GapPolicy};
+use crate::filter::{AccountPattern, FilterRule};
+use crate::verify::VerificationPolicy;
 use clap::{Parser, Subcommand};
 
 const VERSION: Option<&str> = option_env!("CARGO_PKG_VERSION");
@@ -12,6 +17,103 @@ pub(crate) struct Opts {
     pub home: Option<std::path::PathBuf>,
     #[clap(short, long)]
     pub start_block: Option<u64>,
+    /// Also write each block as NEAR Lake compatible JSON files under this directory
+    #[clap(long)]
+    pub lake_output_dir: Option<std::path::PathBuf>,
+    /// Encoding of hashes in `FIRE` lines, block logs and JSON output, defaults to hex
+    /// for `FIRE` lines and block logs and to base58 for JSON
+    #[clap(long, arg_enum)]
+    pub hash_encoding: Option<HashEncoding>,
+    /// Check that each block extends the previously emitted one and what to do if not
+    #[clap(long, arg_enum, default_value = "off")]
+    pub continuity_policy: ContinuityPolicy,
+    /// Check that no block was missed since the previously emitted one and what to do if
+    /// some were
+    #[clap(long, arg_enum, default_value = "off")]
+    pub gap_policy: GapPolicy,
+    /// Emit a `FIRE SKIP` line for each height without a block
+    #[clap(long)]
+    pub emit_skipped_heights: bool,
+    /// Verify execution outcome proofs against the chunk and block outcome roots
+    #[clap(long, arg_enum, default_value = "off")]
+    pub verify_outcomes: VerificationPolicy,
+    /// Verify chunk and block transaction and receipt roots against the converted block
+    #[clap(long, arg_enum, default_value = "off")]
+    pub verify_roots: VerificationPolicy,
+    /// Verify everything and stop on the first failure, meant for archive rebuilds
+    #[clap(long)]
+    pub paranoid: bool,
+    /// Emit zstd compressed `FIRE BLOCK_Z` lines instead of `FIRE BLOCK` lines
+    #[clap(long)]
+    pub compress_blocks: bool,
+    /// zstd compression level of `FIRE BLOCK_Z` payloads
+    #[clap(long, default_value = "3")]
+    pub compression_level: i32,
+    /// Trained zstd dictionary used to compress and decode `FIRE BLOCK_Z` payloads
+    #[clap(long)]
+    pub compression_dictionary: Option<std::path::PathBuf>,
+    /// Also write each block with only its block and chunk headers to this file or named
+    /// pipe
+    #[clap(long)]
+    pub header_only_output: Option<std::path::PathBuf>,
+    /// Only keep transactions, receipts and outcomes matching one of these rules, each of
+    /// the form `<field>=<account>` where field is one of receiver_id, signer_id,
+    /// predecessor_id or executor_id and account can match sub-accounts, as in `*.near`
+    #[clap(long = "filter")]
+    pub filters: Vec<FilterRule>,
+    /// Token contracts whose pre NEP-297 transfer and burn logs are turned into fungible
+    /// token transfers, exact accounts or sub-accounts as in `*.factory.bridge.near`
+    #[clap(
+        long = "legacy-ft-contract",
+        default_values = &["wrap.near", "*.factory.bridge.near"]
+    )]
+    pub legacy_ft_contracts: Vec<AccountPattern>,
+    /// Only keep these shards in emitted blocks, comma separated
+    #[clap(long, use_value_delimiter = true)]
+    pub shards: Vec<u64>,
+    /// Also write each block split per shard to one file or named pipe per shard, with a
+    /// cursor file per shard, under this directory
+    #[clap(long)]
+    pub shard_output_dir: Option<std::path::PathBuf>,
+    /// Annotate receipt execution outcomes with the hash of the transaction they descend
+    /// from and their depth in its receipt tree, and report finalized transactions
+    #[clap(long)]
+    pub track_lineage: bool,
+    /// File where receipt lineage is saved once blocks are emitted and restored from on
+    /// start
+    #[clap(long)]
+    pub lineage_state: Option<std::path::PathBuf>,
+    /// Number of emitted blocks between two saves of the lineage state
+    #[clap(long, default_value = "1")]
+    pub lineage_checkpoint_blocks: u64,
+    /// Number of blocks after which receipts still not executed are forgotten
+    #[clap(long, default_value = "100000")]
+    pub lineage_retention_blocks: u64,
+    /// Emit the state changes of each block
+    #[clap(long)]
+    pub state_changes: bool,
+    /// Derive account balance changes, with their cause, from block state changes
+    #[clap(long)]
+    pub balance_changes: bool,
+    /// Pair block approvals with the account of the block producer that signed them
+    #[clap(long)]
+    pub resolve_approvals: bool,
+    /// Directory where contract code is stored once per SHA-256, deployments and code
+    /// updates only carry the code hash and length when set
+    #[clap(long)]
+    pub contract_code_store: Option<std::path::PathBuf>,
+    /// JSON file of byte limits beyond which function call args, logs, receipt data and
+    /// success values are trimmed, with per contract overrides
+    #[clap(long)]
+    pub trim_config: Option<std::path::PathBuf>,
+    /// Attach function call args decoded to JSON, from JSON, base64 encoded JSON within
+    /// JSON or borsh described by a contract ABI
+    #[clap(long)]
+    pub decode_args: bool,
+    /// NEAR ABI file of a contract, used to decode its borsh args, as `<account>=<path>`,
+    /// requires `--decode-args`
+    #[clap(long = "abi", requires = "decode-args")]
+    pub abis: Vec<AbiFile>,
     #[clap(subcommand)]
     pub subcmd: SubCommand,
 }
@@ -19,4 +121,11 @@ pub(crate) struct Opts {
 #[derive(Subcommand, Debug)]
 pub(crate) enum SubCommand {
     Run,
+    /// Print `FIRE BLOCK` and `FIRE BLOCK_Z` lines read from standard input as JSON
+    Decode,
+    /// Write the contract code with the given SHA-256, hex or base64 encoded, from the
+    /// contract code store to standard output
+    ContractCode {
+        hash: String,
+    },
 }
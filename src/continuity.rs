@@ -0,0 +1,331 @@
+use crate::codec;
+use near_indexer::near_primitives::views::BlockHeaderView;
+use std::fmt::{Display, Formatter};
+
+/// What to do when a block does not extend the previously emitted one.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ContinuityPolicy {
+    /// Emit blocks as they come, without any check
+    Off,
+    /// Stop the indexer with an error
+    Halt,
+    /// Emit a `FIRE UNDO` marker before a block forking off or below the last emitted
+    /// one, other violations being logged
+    Undo,
+}
+
+/// What to do when blocks between the last emitted block and a new block were missed.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum GapPolicy {
+    /// Emit blocks as they come, without any check
+    Off,
+    /// Log the missed blocks and emit the new block
+    Report,
+    /// Stop the indexer with an error
+    Halt,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct BlockRef {
+    pub height: u64,
+    pub hash: codec::CryptoHash,
+}
+
+impl BlockRef {
+    fn from_header(header: &BlockHeaderView) -> Self {
+        BlockRef {
+            height: header.height,
+            hash: codec::CryptoHash::from(header.hash),
+        }
+    }
+
+    fn parent_of(header: &BlockHeaderView) -> Self {
+        BlockRef {
+            height: header.prev_height.unwrap_or(0),
+            hash: codec::CryptoHash::from(header.prev_hash),
+        }
+    }
+}
+
+impl Display for BlockRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{} ({})", self.height, self.hash)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Violation {
+    /// The block height is not above the last emitted one.
+    HeightRegression {
+        last: BlockRef,
+        block: BlockRef,
+        parent: BlockRef,
+    },
+    /// The parent is a block above the last emitted one, blocks were missed.
+    Gap {
+        last: BlockRef,
+        block: BlockRef,
+        parent: BlockRef,
+    },
+    /// The parent is not the last emitted block, the chain forked.
+    Fork {
+        last: BlockRef,
+        block: BlockRef,
+        parent: BlockRef,
+    },
+    /// The parent hash is the last emitted block but the parent height is not its height.
+    ParentHeightMismatch {
+        last: BlockRef,
+        block: BlockRef,
+        parent: BlockRef,
+    },
+}
+
+impl Violation {
+    pub fn last(&self) -> &BlockRef {
+        match self {
+            Violation::HeightRegression { last, .. }
+            | Violation::Gap { last, .. }
+            | Violation::Fork { last, .. }
+            | Violation::ParentHeightMismatch { last, .. } => last,
+        }
+    }
+
+    pub fn block(&self) -> &BlockRef {
+        match self {
+            Violation::HeightRegression { block, .. }
+            | Violation::Gap { block, .. }
+            | Violation::Fork { block, .. }
+            | Violation::ParentHeightMismatch { block, .. } => block,
+        }
+    }
+
+    pub fn parent(&self) -> &BlockRef {
+        match self {
+            Violation::HeightRegression { parent, .. }
+            | Violation::Gap { parent, .. }
+            | Violation::Fork { parent, .. }
+            | Violation::ParentHeightMismatch { parent, .. } => parent,
+        }
+    }
+
+    /// Whether consumers must undo blocks down to the common ancestor of the last
+    /// emitted block and the new one, which only forks and height regressions require.
+    pub fn requires_undo(&self) -> bool {
+        matches!(
+            self,
+            Violation::HeightRegression { .. } | Violation::Fork { .. }
+        )
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::HeightRegression { last, block, .. } => write!(
+                f,
+                "block {} height is not above last emitted block {}",
+                block, last
+            ),
+            Violation::Gap {
+                last,
+                block,
+                parent,
+            } => write!(
+                f,
+                "block {} parent {} is above last emitted block {}, blocks were missed",
+                block, parent, last
+            ),
+            Violation::Fork {
+                last,
+                block,
+                parent,
+            } => write!(
+                f,
+                "block {} parent {} is not last emitted block {}",
+                block, parent, last
+            ),
+            Violation::ParentHeightMismatch {
+                last,
+                block,
+                parent,
+            } => write!(
+                f,
+                "block {} parent height {} does not match last emitted block {}",
+                block, parent.height, last
+            ),
+        }
+    }
+}
+
+/// Keeps the last emitted block and checks that each new block is its child.
+pub(crate) struct ContinuityGuard {
+    last: Option<BlockRef>,
+}
+
+impl ContinuityGuard {
+    pub fn new() -> Self {
+        ContinuityGuard { last: None }
+    }
+
+    /// Checks `header` against the last emitted block, the first block seen is always
+    /// accepted.
+    pub fn check(&self, header: &BlockHeaderView) -> Result<(), Violation> {
+        self.check_refs(BlockRef::from_header(header), BlockRef::parent_of(header))
+    }
+
+    fn check_refs(&self, block: BlockRef, parent: BlockRef) -> Result<(), Violation> {
+        let last = match &self.last {
+            None => return Ok(()),
+            Some(last) => last.clone(),
+        };
+
+        if block.height <= last.height {
+            return Err(Violation::HeightRegression {
+                last,
+                block,
+                parent,
+            });
+        }
+
+        if parent.hash == last.hash {
+            if parent.height != last.height {
+                return Err(Violation::ParentHeightMismatch {
+                    last,
+                    block,
+                    parent,
+                });
+            }
+
+            return Ok(());
+        }
+
+        if parent.height > last.height {
+            return Err(Violation::Gap {
+                last,
+                block,
+                parent,
+            });
+        }
+
+        Err(Violation::Fork {
+            last,
+            block,
+            parent,
+        })
+    }
+
+    /// Records `header` as the last emitted block.
+    pub fn advance(&mut self, header: &BlockHeaderView) {
+        self.last = Some(BlockRef::from_header(header));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(height: u64, hash: u8) -> BlockRef {
+        BlockRef {
+            height,
+            hash: codec::CryptoHash {
+                bytes: vec![hash; 32],
+            },
+        }
+    }
+
+    fn guard_at(last: BlockRef) -> ContinuityGuard {
+        ContinuityGuard { last: Some(last) }
+    }
+
+    #[test]
+    fn first_block_is_accepted() {
+        assert_eq!(
+            ContinuityGuard::new().check_refs(block(7, 7), block(3, 3)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn child_is_accepted() {
+        let guard = guard_at(block(10, 1));
+
+        assert_eq!(guard.check_refs(block(11, 2), block(10, 1)), Ok(()));
+        // Skipped heights leave the parent below the previous height.
+        assert_eq!(guard.check_refs(block(14, 2), block(10, 1)), Ok(()));
+    }
+
+    #[test]
+    fn height_regression_requires_undo() {
+        let guard = guard_at(block(10, 1));
+
+        for height in [9, 10] {
+            let violation = guard.check_refs(block(height, 2), block(8, 3)).unwrap_err();
+            assert_eq!(
+                violation,
+                Violation::HeightRegression {
+                    last: block(10, 1),
+                    block: block(height, 2),
+                    parent: block(8, 3),
+                }
+            );
+            assert!(violation.requires_undo());
+        }
+    }
+
+    #[test]
+    fn fork_requires_undo() {
+        let guard = guard_at(block(10, 1));
+
+        let violation = guard.check_refs(block(11, 2), block(9, 3)).unwrap_err();
+        assert_eq!(
+            violation,
+            Violation::Fork {
+                last: block(10, 1),
+                block: block(11, 2),
+                parent: block(9, 3),
+            }
+        );
+        assert!(violation.requires_undo());
+
+        // A sibling of the last block at the same parent height is a fork too.
+        let violation = guard.check_refs(block(11, 2), block(10, 3)).unwrap_err();
+        assert!(matches!(violation, Violation::Fork { .. }));
+    }
+
+    #[test]
+    fn gap_does_not_require_undo() {
+        let guard = guard_at(block(10, 1));
+
+        let violation = guard.check_refs(block(13, 2), block(12, 3)).unwrap_err();
+        assert_eq!(
+            violation,
+            Violation::Gap {
+                last: block(10, 1),
+                block: block(13, 2),
+                parent: block(12, 3),
+            }
+        );
+        assert!(!violation.requires_undo());
+    }
+
+    #[test]
+    fn parent_height_mismatch_does_not_require_undo() {
+        let guard = guard_at(block(10, 1));
+
+        let violation = guard.check_refs(block(12, 2), block(11, 1)).unwrap_err();
+        assert!(matches!(violation, Violation::ParentHeightMismatch { .. }));
+        assert!(!violation.requires_undo());
+    }
+
+    #[test]
+    fn violation_refs() {
+        let violation = guard_at(block(10, 1))
+            .check_refs(block(11, 2), block(9, 3))
+            .unwrap_err();
+
+        assert_eq!(violation.last(), &block(10, 1));
+        assert_eq!(violation.block(), &block(11, 2));
+        assert_eq!(violation.parent(), &block(9, 3));
+    }
+}
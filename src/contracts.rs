@@ -0,0 +1,151 @@
+use crate::codec;
+use crate::codec::state_change_value::Value;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counter making the temporary file names of a process unique.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Content-addressed store of contract code. Each code is written once, to a file named
+/// after its hex SHA-256 with a `.wasm` extension, through a uniquely named temporary
+/// file synced to disk along with the directory, so that a stored code is never partial.
+pub(crate) struct CodeStore {
+    dir: PathBuf,
+    stored: HashSet<Vec<u8>>,
+}
+
+impl CodeStore {
+    pub fn new(dir: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+
+        Ok(CodeStore {
+            dir,
+            stored: HashSet::new(),
+        })
+    }
+
+    /// Moves the code of the block contract deployments and code updates to the store,
+    /// leaving its hash and length in their place.
+    pub fn dedup(&mut self, block: &mut codec::Block) -> io::Result<()> {
+        for shard in block.shards.iter_mut() {
+            if let Some(chunk) = shard.chunk.as_mut() {
+                for tx in chunk.transactions.iter_mut() {
+                    if let Some(signed) = tx.transaction.as_mut() {
+                        self.dedup_actions(&mut signed.actions)?;
+                    }
+                }
+                for receipt in chunk.receipts.iter_mut() {
+                    self.dedup_receipt(receipt)?;
+                }
+            }
+
+            for outcome in shard.receipt_execution_outcomes.iter_mut() {
+                if let Some(receipt) = outcome.receipt.as_mut() {
+                    self.dedup_receipt(receipt)?;
+                }
+            }
+        }
+
+        for change in block.state_changes.iter_mut() {
+            if let Some(Value::ContractCodeUpdate(update)) =
+                change.value.as_mut().and_then(|v| v.value.as_mut())
+            {
+                update.code_len = update.code.len() as u64;
+                update.code_hash = self.put(std::mem::take(&mut update.code))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn dedup_receipt(&mut self, receipt: &mut codec::Receipt) -> io::Result<()> {
+        match receipt.receipt.as_mut() {
+            Some(codec::receipt::Receipt::Action(action)) => {
+                self.dedup_actions(&mut action.actions)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn dedup_actions(&mut self, actions: &mut [codec::Action]) -> io::Result<()> {
+        for action in actions {
+            match action.action.as_mut() {
+                Some(codec::action::Action::DeployContract(deploy)) => {
+                    deploy.code_len = deploy.code.len() as u64;
+                    deploy.code_hash = self.put(std::mem::take(&mut deploy.code))?;
+                }
+                Some(codec::action::Action::Delegate(signed)) => {
+                    if let Some(delegate) = signed.delegate_action.as_mut() {
+                        self.dedup_actions(&mut delegate.actions)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn put(&mut self, code: Vec<u8>) -> io::Result<Vec<u8>> {
+        let hash = Sha256::digest(&code).to_vec();
+        if self.stored.contains(&hash) {
+            return Ok(hash);
+        }
+
+        let path = code_path(&self.dir, &hash);
+        if !path.exists() {
+            let tmp = self.dir.join(format!(
+                "{}.{}.{}.tmp",
+                hex::encode(&hash),
+                std::process::id(),
+                TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            let mut file = File::create(&tmp)?;
+            file.write_all(&code)?;
+            file.sync_all()?;
+            fs::rename(&tmp, &path)?;
+            File::open(&self.dir)?.sync_all()?;
+        }
+        self.stored.insert(hash.clone());
+
+        Ok(hash)
+    }
+}
+
+fn code_path(dir: &Path, hash: &[u8]) -> PathBuf {
+    dir.join(format!("{}.wasm", hex::encode(hash)))
+}
+
+/// Reads the contract code stored in `dir` under `hash`, hex or base64 encoded as in
+/// decoded blocks, and checks it against the hash.
+pub(crate) fn read(dir: &Path, hash: &str) -> io::Result<Vec<u8>> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid code hash {:?}", hash),
+        )
+    };
+    let hash = match hex::decode(hash) {
+        Ok(hash) => hash,
+        Err(_) => STANDARD.decode(hash).map_err(|_| invalid())?,
+    };
+    if hash.len() != 32 {
+        return Err(invalid());
+    }
+
+    let code = fs::read(code_path(dir, &hash))?;
+    if Sha256::digest(&code).as_slice() != hash.as_slice() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("contract code {} is corrupted", hex::encode(&hash)),
+        ));
+    }
+
+    Ok(code)
+}
@@ -0,0 +1,34 @@
+use crate::codec;
+use std::io::{BufRead, Error, ErrorKind, Write};
+
+/// Reads Firehose `FIRE BLOCK` and `FIRE BLOCK_Z` lines from standard input and prints
+/// each block as canonical JSON, one block per line, with hashes in `hash_encoding`. Any
+/// other line is ignored.
+pub(crate) fn run(
+    compression_dictionary: Option<&[u8]>,
+    hash_encoding: codec::HashEncoding,
+) -> std::io::Result<()> {
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let block: codec::Block = if let Some(fields) = line.strip_prefix("FIRE BLOCK ") {
+            let payload = fields.rsplit(' ').next().unwrap_or_default();
+            let bytes = hex::decode(payload).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+            prost::Message::decode(bytes.as_slice())
+                .map_err(|e| Error::new(ErrorKind::InvalidData, e))?
+        } else if let Some(fields) = line.strip_prefix("FIRE BLOCK_Z ") {
+            let payload = fields.rsplit(' ').next().unwrap_or_default();
+            codec::decode_compressed_block(payload, compression_dictionary)?
+        } else {
+            continue;
+        };
+
+        codec::with_hash_encoding(hash_encoding, || serde_json::to_writer(&mut out, &block))?;
+        writeln!(out)?;
+    }
+
+    out.flush()
+}
@@ -0,0 +1,12 @@
+use crate::codec;
+use crate::events;
+use crate::filter::AccountPattern;
+use crate::tokens;
+
+/// Fills the block streams derived from its receipts, events and token activity. It
+/// runs after filtering so that derived entries only refer to receipts in the block.
+pub(crate) fn derive(block: &mut codec::Block, legacy_ft_contracts: &[AccountPattern]) {
+    block.events = events::extract(&block.shards);
+    block.ft_transfers = tokens::extract_ft_transfers(&block.shards, legacy_ft_contracts);
+    block.nft_activities = tokens::extract_nft_activities(&block.shards);
+}
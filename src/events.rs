@@ -0,0 +1,163 @@
+use crate::codec;
+use serde_json::Value;
+use tracing::debug;
+
+const EVENT_LOG_PREFIX: &str = "EVENT_JSON:";
+
+/// NEP-297 event parsed from a receipt log.
+pub(crate) struct ParsedEvent {
+    pub standard: String,
+    pub version: String,
+    pub event: String,
+    pub data: Option<Value>,
+}
+
+/// Parses an `EVENT_JSON:` prefixed log. `None` is returned for other logs as well as
+/// for event logs that are not a JSON object with string `standard`, `version` and
+/// `event` members.
+pub(crate) fn parse(log: &str) -> Option<ParsedEvent> {
+    let json = log.strip_prefix(EVENT_LOG_PREFIX)?;
+    let Value::Object(mut event) = serde_json::from_str(json.trim()).ok()? else {
+        return None;
+    };
+
+    let mut member = |name: &str| match event.remove(name) {
+        Some(Value::String(value)) => Some(value),
+        _ => None,
+    };
+    let standard = member("standard")?;
+    let version = member("version")?;
+    let name = member("event")?;
+
+    Some(ParsedEvent {
+        standard,
+        version,
+        event: name,
+        data: event.remove("data"),
+    })
+}
+
+/// Extracts the NEP-297 events logged by the receipts executed in `shards`, malformed
+/// event logs are skipped. Transaction outcomes are not scanned, converting a transaction
+/// into a receipt runs no contract code and logs nothing.
+pub(crate) fn extract(shards: &[codec::IndexerShard]) -> Vec<codec::Event> {
+    let mut events = vec![];
+
+    for outcome in shards
+        .iter()
+        .flat_map(|shard| shard.receipt_execution_outcomes.iter())
+        .filter_map(|o| o.execution_outcome.as_ref())
+    {
+        let Some(execution_outcome) = outcome.outcome.as_ref() else {
+            continue;
+        };
+
+        for (log_index, log) in execution_outcome.logs.iter().enumerate() {
+            if !log.starts_with(EVENT_LOG_PREFIX) {
+                continue;
+            }
+
+            match parse(log) {
+                Some(parsed) => events.push(codec::Event {
+                    standard: parsed.standard,
+                    version: parsed.version,
+                    event: parsed.event,
+                    data: parsed.data.map(|d| d.to_string()).unwrap_or_default(),
+                    receipt_id: outcome.id.clone(),
+                    executor_id: execution_outcome.executor_id.clone(),
+                    log_index: log_index as u32,
+                }),
+                None => {
+                    if let Some(receipt_id) = &outcome.id {
+                        debug!(
+                            target: "firehose",
+                            "Skipping malformed event log of receipt {}",
+                            receipt_id,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_events() {
+        let data = serde_json::json!([{"owner_id": "alice.near", "amount": "1"}]);
+        let log = serde_json::json!({
+            "standard": "nep141",
+            "version": "1.0.0",
+            "event": "ft_mint",
+            "data": data,
+        });
+        let event = parse(&format!("{}{}", EVENT_LOG_PREFIX, log)).unwrap();
+        assert_eq!(event.standard, "nep141");
+        assert_eq!(event.version, "1.0.0");
+        assert_eq!(event.event, "ft_mint");
+        assert_eq!(event.data.unwrap(), data);
+
+        let event = parse(r#"EVENT_JSON: {"standard":"a","version":"1","event":"b"}"#).unwrap();
+        assert_eq!(event.event, "b");
+        assert!(event.data.is_none());
+    }
+
+    #[test]
+    fn malformed_event_logs_are_skipped() {
+        for log in [
+            "EVENT_JSON:",
+            "EVENT_JSON:{",
+            r#"EVENT_JSON:["standard"]"#,
+            r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0"}"#,
+            r#"EVENT_JSON:{"standard":"nep141","version":1,"event":"ft_mint"}"#,
+        ] {
+            assert!(parse(log).is_none(), "{}", log);
+        }
+    }
+
+    #[test]
+    fn other_logs_are_not_events() {
+        for log in [
+            "Transfer 1 from alice.near to bob.near",
+            r#"{"standard":"nep141","version":"1.0.0","event":"ft_mint"}"#,
+            r#"event_json:{"standard":"nep141","version":"1.0.0","event":"ft_mint"}"#,
+        ] {
+            assert!(parse(log).is_none(), "{}", log);
+        }
+    }
+
+    #[test]
+    fn extracts_events_of_receipt_outcomes() {
+        let logs = vec![
+            "Transfer".to_string(),
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_burn"}"#.to_string(),
+            "EVENT_JSON:{".to_string(),
+        ];
+        let shard = codec::IndexerShard {
+            receipt_execution_outcomes: vec![codec::IndexerExecutionOutcomeWithReceipt {
+                execution_outcome: Some(codec::ExecutionOutcomeWithId {
+                    outcome: Some(codec::ExecutionOutcome {
+                        logs,
+                        executor_id: "nft.near".to_string(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let events = extract(&[shard]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, "nft_burn");
+        assert_eq!(events[0].executor_id, "nft.near");
+        assert_eq!(events[0].log_index, 1);
+        assert!(events[0].data.is_empty());
+    }
+}
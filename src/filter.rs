@@ -0,0 +1,433 @@
+use crate::codec;
+use sha2::{Digest, Sha256};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum AccountField {
+    ReceiverId,
+    SignerId,
+    PredecessorId,
+    ExecutorId,
+}
+
+/// Exact account or, with a `*.` prefix, any sub-account of it (`*.near` matches
+/// `alice.near` and `app.alice.near` but not `near`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum AccountPattern {
+    Exact(String),
+    SubAccounts(String),
+}
+
+impl AccountPattern {
+    pub fn matches(&self, account_id: &str) -> bool {
+        match self {
+            AccountPattern::Exact(id) => account_id == id,
+            AccountPattern::SubAccounts(suffix) => {
+                account_id.len() > suffix.len() && account_id.ends_with(suffix.as_str())
+            }
+        }
+    }
+}
+
+impl FromStr for AccountPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('*') {
+            Some(suffix) if suffix.starts_with('.') && suffix.len() > 1 => {
+                Ok(AccountPattern::SubAccounts(suffix.to_string()))
+            }
+            Some(_) => Err(format!("invalid account pattern {:?}", s)),
+            None if !s.is_empty() => Ok(AccountPattern::Exact(s.to_string())),
+            None => Err("empty account pattern".to_string()),
+        }
+    }
+}
+
+/// Filter rule of the form `<field>=<pattern>`, for example `receiver_id=*.aurora`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FilterRule {
+    field: AccountField,
+    pattern: AccountPattern,
+}
+
+impl FromStr for FilterRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (field, pattern) = s
+            .split_once('=')
+            .ok_or_else(|| format!("invalid filter rule {:?}, expected <field>=<account>", s))?;
+
+        let field = match field {
+            "receiver_id" => AccountField::ReceiverId,
+            "signer_id" => AccountField::SignerId,
+            "predecessor_id" => AccountField::PredecessorId,
+            "executor_id" => AccountField::ExecutorId,
+            _ => return Err(format!("invalid filter field {:?}", field)),
+        };
+
+        let pattern = pattern
+            .parse()
+            .map_err(|err| format!("invalid filter rule {:?}, {}", s, err))?;
+
+        Ok(FilterRule { field, pattern })
+    }
+}
+
+impl Display for FilterRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let field = match self.field {
+            AccountField::ReceiverId => "receiver_id",
+            AccountField::SignerId => "signer_id",
+            AccountField::PredecessorId => "predecessor_id",
+            AccountField::ExecutorId => "executor_id",
+        };
+
+        match &self.pattern {
+            AccountPattern::Exact(id) => write!(f, "{}={}", field, id),
+            AccountPattern::SubAccounts(suffix) => write!(f, "{}=*{}", field, suffix),
+        }
+    }
+}
+
+/// Prunes shards not selected and transactions, receipts, execution outcomes and state
+/// changes that match none of the account rules from blocks. Blocks something was pruned
+/// from are marked as filtered and carry the SHA-256 of the rules, sorted and newline
+/// separated, selected shards being rendered as `shard_id=<id>` rules.
+pub(crate) struct BlockFilter {
+    rules: Vec<FilterRule>,
+    shard_ids: Vec<u64>,
+    hash: Vec<u8>,
+}
+
+impl BlockFilter {
+    pub fn new(rules: Vec<FilterRule>, shard_ids: Vec<u64>) -> Self {
+        let mut canonical: Vec<String> = rules
+            .iter()
+            .map(|r| r.to_string())
+            .chain(shard_ids.iter().map(|id| format!("shard_id={}", id)))
+            .collect();
+        canonical.sort();
+        canonical.dedup();
+
+        BlockFilter {
+            rules,
+            shard_ids,
+            hash: Sha256::digest(canonical.join("\n")).to_vec(),
+        }
+    }
+
+    pub fn hash(&self) -> &[u8] {
+        &self.hash
+    }
+
+    pub fn apply(&self, block: &mut codec::Block) {
+        let mut pruned = false;
+
+        if !self.shard_ids.is_empty() {
+            let selected = |shard_id: &u64| self.shard_ids.contains(shard_id);
+            pruned |= retain(&mut block.shards, |shard| selected(&shard.shard_id));
+            pruned |= retain(&mut block.state_changes, |change| {
+                selected(&change.shard_id)
+            });
+            pruned |= retain(&mut block.balance_changes, |change| {
+                selected(&change.shard_id)
+            });
+            pruned |= retain(&mut block.finalized_transactions, |tx| {
+                selected(&tx.shard_id)
+            });
+        }
+
+        if !self.rules.is_empty() {
+            for shard in block.shards.iter_mut() {
+                if let Some(chunk) = shard.chunk.as_mut() {
+                    pruned |= retain(&mut chunk.transactions, |tx| self.transaction_matches(tx));
+                    pruned |= retain(&mut chunk.receipts, |r| self.receipt_matches(r));
+                }
+
+                pruned |= retain(&mut shard.receipt_execution_outcomes, |o| {
+                    self.outcome_matches(o)
+                });
+            }
+
+            pruned |= retain(&mut block.finalized_transactions, |tx| {
+                self.matches(AccountField::SignerId, &tx.signer_id)
+                    || self.matches(AccountField::ReceiverId, &tx.receiver_id)
+            });
+            pruned |= retain(&mut block.state_changes, |change| {
+                self.state_change_matches(change)
+            });
+            pruned |= retain(&mut block.balance_changes, |change| {
+                let by_transaction = change.cause == codec::BalanceChangeCause::Transaction as i32;
+                self.change_matches(by_transaction, &change.account_id)
+            });
+        }
+
+        if pruned {
+            block.filtering_applied = true;
+            block.filter_hash = self.hash.clone();
+        }
+    }
+
+    fn matches(&self, field: AccountField, account_id: &str) -> bool {
+        self.rules
+            .iter()
+            .any(|r| r.field == field && r.pattern.matches(account_id))
+    }
+
+    /// State and balance changes made by processing a transaction are charged to its
+    /// signer, any other change is made to the receiver executing a receipt.
+    fn change_matches(&self, by_transaction: bool, account_id: &str) -> bool {
+        match by_transaction {
+            true => self.matches(AccountField::SignerId, account_id),
+            false => {
+                self.matches(AccountField::ReceiverId, account_id)
+                    || self.matches(AccountField::ExecutorId, account_id)
+            }
+        }
+    }
+
+    fn state_change_matches(&self, change: &codec::StateChangeWithCause) -> bool {
+        use codec::state_change_cause::Cause;
+        use codec::state_change_value::Value;
+
+        let account_id = match change.value.as_ref().and_then(|v| v.value.as_ref()) {
+            Some(Value::AccountUpdate(v)) => &v.account_id,
+            Some(Value::AccountDeletion(v)) => &v.account_id,
+            Some(Value::AccessKeyUpdate(v)) => &v.account_id,
+            Some(Value::AccessKeyDeletion(v)) => &v.account_id,
+            Some(Value::DataUpdate(v)) => &v.account_id,
+            Some(Value::DataDeletion(v)) => &v.account_id,
+            Some(Value::ContractCodeUpdate(v)) => &v.account_id,
+            Some(Value::ContractDeletion(v)) => &v.account_id,
+            None => return false,
+        };
+
+        let by_transaction = matches!(
+            change.cause.as_ref().and_then(|c| c.cause.as_ref()),
+            Some(Cause::TransactionProcessing(_))
+        );
+        self.change_matches(by_transaction, account_id)
+    }
+
+    fn executor_matches(&self, outcome: &Option<codec::ExecutionOutcomeWithId>) -> bool {
+        outcome
+            .as_ref()
+            .and_then(|o| o.outcome.as_ref())
+            .map_or(false, |o| {
+                self.matches(AccountField::ExecutorId, &o.executor_id)
+            })
+    }
+
+    fn transaction_matches(&self, tx: &codec::IndexerTransactionWithOutcome) -> bool {
+        let signed = tx.transaction.as_ref().map_or(false, |t| {
+            self.matches(AccountField::SignerId, &t.signer_id)
+                || self.matches(AccountField::ReceiverId, &t.receiver_id)
+        });
+
+        signed
+            || tx
+                .outcome
+                .as_ref()
+                .map_or(false, |o| self.executor_matches(&o.execution_outcome))
+    }
+
+    fn receipt_matches(&self, r: &codec::Receipt) -> bool {
+        let signer_matches = match &r.receipt {
+            Some(codec::receipt::Receipt::Action(a)) => {
+                self.matches(AccountField::SignerId, &a.signer_id)
+            }
+            _ => false,
+        };
+
+        signer_matches
+            || self.matches(AccountField::ReceiverId, &r.receiver_id)
+            || self.matches(AccountField::PredecessorId, &r.predecessor_id)
+    }
+
+    fn outcome_matches(&self, o: &codec::IndexerExecutionOutcomeWithReceipt) -> bool {
+        o.receipt
+            .as_ref()
+            .map_or(false, |r| self.receipt_matches(r))
+            || self.executor_matches(&o.execution_outcome)
+    }
+}
+
+/// Retains the matching items, telling whether any was removed.
+fn retain<T>(items: &mut Vec<T>, keep: impl FnMut(&T) -> bool) -> bool {
+    let len = items.len();
+    items.retain(keep);
+    items.len() != len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::state_change_cause::{Cause, ReceiptProcessing, TransactionProcessing};
+    use codec::state_change_value::{AccountUpdate, Value};
+
+    fn filter(rules: &[&str]) -> BlockFilter {
+        let rules = rules.iter().map(|r| r.parse().unwrap()).collect();
+        BlockFilter::new(rules, Vec::new())
+    }
+
+    fn receipt(predecessor_id: &str, receiver_id: &str) -> codec::Receipt {
+        codec::Receipt {
+            predecessor_id: predecessor_id.to_string(),
+            receiver_id: receiver_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn state_change(account_id: &str, cause: Cause) -> codec::StateChangeWithCause {
+        codec::StateChangeWithCause {
+            value: Some(codec::StateChangeValue {
+                value: Some(Value::AccountUpdate(AccountUpdate {
+                    account_id: account_id.to_string(),
+                    account: None,
+                })),
+            }),
+            cause: Some(codec::StateChangeCause { cause: Some(cause) }),
+            shard_id: 0,
+        }
+    }
+
+    fn block(receipts: Vec<codec::Receipt>) -> codec::Block {
+        codec::Block {
+            shards: vec![codec::IndexerShard {
+                chunk: Some(codec::IndexerChunk {
+                    receipts,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn receivers(block: &codec::Block) -> Vec<&str> {
+        let chunk = block.shards[0].chunk.as_ref().unwrap();
+        chunk
+            .receipts
+            .iter()
+            .map(|r| r.receiver_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn patterns() {
+        let exact: AccountPattern = "aurora".parse().unwrap();
+        assert!(exact.matches("aurora"));
+        assert!(!exact.matches("relay.aurora"));
+        assert!(!exact.matches("aurora.near"));
+
+        let suffix: AccountPattern = "*.near".parse().unwrap();
+        assert!(suffix.matches("alice.near"));
+        assert!(suffix.matches("app.alice.near"));
+        assert!(!suffix.matches("near"));
+        assert!(!suffix.matches("alicenear"));
+        assert!(!suffix.matches("alice.near.org"));
+
+        for invalid in ["", "*", "*.", "*near"] {
+            assert!(invalid.parse::<AccountPattern>().is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn rules_round_trip() {
+        for rule in [
+            "receiver_id=aurora",
+            "signer_id=*.near",
+            "executor_id=*.aurora",
+        ] {
+            assert_eq!(rule.parse::<FilterRule>().unwrap().to_string(), rule);
+        }
+        assert!("account_id=aurora".parse::<FilterRule>().is_err());
+        assert!("receiver_id".parse::<FilterRule>().is_err());
+    }
+
+    #[test]
+    fn exact_and_wildcard_rules_prune_receipts() {
+        let filter = filter(&["receiver_id=aurora", "receiver_id=*.aurora"]);
+        let mut block = block(vec![
+            receipt("alice.near", "aurora"),
+            receipt("alice.near", "relay.aurora"),
+            receipt("aurora", "bob.near"),
+        ]);
+
+        filter.apply(&mut block);
+        assert_eq!(receivers(&block), ["aurora", "relay.aurora"]);
+        assert!(block.filtering_applied);
+        assert_eq!(block.filter_hash, filter.hash());
+    }
+
+    #[test]
+    fn non_matching_rules_prune_everything() {
+        let filter = filter(&["predecessor_id=*.aurora"]);
+        let mut block = block(vec![receipt("alice.near", "aurora")]);
+
+        filter.apply(&mut block);
+        assert!(receivers(&block).is_empty());
+        assert!(block.filtering_applied);
+    }
+
+    #[test]
+    fn blocks_nothing_is_pruned_from_are_not_marked() {
+        let filter = filter(&["predecessor_id=alice.near"]);
+        let mut block = block(vec![receipt("alice.near", "aurora")]);
+
+        filter.apply(&mut block);
+        assert_eq!(receivers(&block), ["aurora"]);
+        assert!(!block.filtering_applied);
+        assert!(block.filter_hash.is_empty());
+    }
+
+    #[test]
+    fn changes_match_per_field() {
+        let by_transaction = || Cause::TransactionProcessing(TransactionProcessing::default());
+        let by_receipt = || Cause::ReceiptProcessing(ReceiptProcessing::default());
+        let changes = || {
+            vec![
+                state_change("alice.near", by_transaction()),
+                state_change("alice.near", by_receipt()),
+            ]
+        };
+        let balance_change = |cause: codec::BalanceChangeCause| codec::BalanceChange {
+            account_id: "alice.near".to_string(),
+            cause: cause.into(),
+            ..Default::default()
+        };
+
+        let mut block = codec::Block {
+            state_changes: changes(),
+            balance_changes: vec![
+                balance_change(codec::BalanceChangeCause::Transaction),
+                balance_change(codec::BalanceChangeCause::Receipt),
+            ],
+            ..Default::default()
+        };
+        filter(&["signer_id=alice.near"]).apply(&mut block);
+        assert_eq!(block.state_changes, &changes()[..1]);
+        assert_eq!(block.balance_changes.len(), 1);
+        assert_eq!(
+            block.balance_changes[0].cause,
+            codec::BalanceChangeCause::Transaction as i32
+        );
+
+        let mut block = codec::Block {
+            state_changes: changes(),
+            ..Default::default()
+        };
+        filter(&["receiver_id=*.near"]).apply(&mut block);
+        assert_eq!(block.state_changes, &changes()[1..]);
+
+        let mut block = codec::Block {
+            state_changes: changes(),
+            ..Default::default()
+        };
+        filter(&["predecessor_id=alice.near"]).apply(&mut block);
+        assert!(block.state_changes.is_empty());
+    }
+}
@@ -1,12 +1,208 @@
 use crate::codec;
-use tracing::info;
+use crate::continuity::Violation;
+use base64::engine::general_purpose::STANDARD;
+use base64::write::EncoderWriter;
+use prost::Message;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Stdout, Write};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use tracing::{error, info};
+
+const OUTPUT_BUFFER_SIZE: usize = 1024 * 1024;
+const RETAINED_BLOCK_BUFFER_SIZE: usize = 16 * 1024 * 1024;
+const HEX_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Writes `FIRE` lines through a single large buffer, flushed once each line is
+/// complete. Blocks are encoded into a reused buffer and hex (or base64 once
+/// compressed) encoded straight into the output, so the text form of a block is never
+/// held in memory.
+pub(crate) struct Output<W: Write> {
+    writer: BufWriter<W>,
+    block: Vec<u8>,
+    compressor: Option<codec::BlockCompressor>,
+    hash_encoding: codec::HashEncoding,
+}
+
+/// Writer of the standard output, process wide like standard output itself. Its hash
+/// encoding is a setting of the writer, see [set_hash_encoding].
+fn output() -> MutexGuard<'static, Output<Stdout>> {
+    static OUTPUT: OnceLock<Mutex<Output<Stdout>>> = OnceLock::new();
+
+    OUTPUT
+        .get_or_init(|| Mutex::new(Output::new(io::stdout())))
+        .lock()
+        .unwrap()
+}
+
+/// Marker line written between blocks to every output.
+pub(crate) enum Marker<'a> {
+    /// `FIRE UNDO`, the new block forks off the last emitted block.
+    Undo(&'a Violation),
+    /// `FIRE SKIP`, a height without a block.
+    Skip(u64),
+}
+
+/// Secondary output receiving `FIRE BLOCK` lines with a `HeaderOnlyBlock` payload, set
+/// once on start with its own hash encoding.
+static HEADER_ONLY_OUTPUT: OnceLock<Mutex<Output<File>>> = OnceLock::new();
+
+impl<W: Write> Output<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Output {
+            writer: BufWriter::with_capacity(OUTPUT_BUFFER_SIZE, writer),
+            block: Vec::new(),
+            compressor: None,
+            hash_encoding: codec::HashEncoding::Hex,
+        }
+    }
+
+    pub(crate) fn set_compressor(&mut self, compressor: codec::BlockCompressor) {
+        self.compressor = Some(compressor);
+    }
+
+    pub(crate) fn set_hash_encoding(&mut self, encoding: codec::HashEncoding) {
+        self.hash_encoding = encoding;
+    }
+
+    fn write_line(&mut self, line: fmt::Arguments) -> io::Result<()> {
+        self.writer.write_fmt(line)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+
+    pub(crate) fn write_block(
+        &mut self,
+        header: &codec::BlockHeader,
+        block: &impl Message,
+    ) -> io::Result<()> {
+        self.block.clear();
+        self.block.reserve(block.encoded_len());
+        block
+            .encode(&mut self.block)
+            .expect("buffer has been reserved for the whole block");
+
+        write!(
+            self.writer,
+            "FIRE {kind:} {height:} {hash:} {parent_height:} {parent_hash:} {lib:} {timestamp:} ",
+            kind = match self.compressor {
+                Some(_) => "BLOCK_Z",
+                None => "BLOCK",
+            },
+            height = header.height,
+            hash = header.hash.as_ref().unwrap().display(self.hash_encoding),
+            parent_height = header.prev_height,
+            parent_hash = header
+                .prev_hash
+                .as_ref()
+                .unwrap()
+                .display(self.hash_encoding),
+            lib = header
+                .last_final_block
+                .as_ref()
+                .unwrap()
+                .display(self.hash_encoding),
+            timestamp = header.timestamp,
+        )?;
+
+        match &mut self.compressor {
+            Some(compressor) => {
+                let compressed = compressor.compress(&self.block)?;
+                let mut encoder = EncoderWriter::new(&mut self.writer, &STANDARD);
+                encoder.write_all(&compressed)?;
+                encoder.finish()?;
+            }
+            None => {
+                let mut hex = [0u8; HEX_CHUNK_SIZE * 2];
+                for chunk in self.block.chunks(HEX_CHUNK_SIZE) {
+                    let hex = &mut hex[..chunk.len() * 2];
+                    hex::encode_to_slice(chunk, hex).expect("hex buffer is twice the chunk size");
+                    self.writer.write_all(hex)?;
+                }
+            }
+        }
+        self.block.shrink_to(RETAINED_BLOCK_BUFFER_SIZE);
+
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+
+    /// The `FIRE UNDO` line carries the last emitted block followed by the new block and
+    /// its parent.
+    pub(crate) fn write_marker(&mut self, marker: &Marker) -> io::Result<()> {
+        let encoding = self.hash_encoding;
+
+        match marker {
+            Marker::Undo(violation) => {
+                let last = violation.last();
+                let block = violation.block();
+                let parent = violation.parent();
+
+                self.write_line(format_args!(
+                    "FIRE UNDO {last_height:} {last_hash:} {height:} {hash:} {parent_height:} {parent_hash:}",
+                    last_height = last.height,
+                    last_hash = last.hash.display(encoding),
+                    height = block.height,
+                    hash = block.hash.display(encoding),
+                    parent_height = parent.height,
+                    parent_hash = parent.hash.display(encoding),
+                ))
+            }
+            Marker::Skip(height) => {
+                self.write_line(format_args!("FIRE SKIP {height:}", height = height))
+            }
+        }
+    }
+}
+
+fn record_marker(marker: Marker) {
+    if let Err(err) = output().write_marker(&marker) {
+        error!(target: "main", "Failed to write to standard output: {}", err);
+        std::process::exit(1);
+    }
+
+    if let Some(header_only_output) = HEADER_ONLY_OUTPUT.get() {
+        let mut header_only_output = header_only_output.lock().unwrap();
+        if let Err(err) = header_only_output.write_marker(&marker) {
+            error!(target: "main", "Failed to write header only marker: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Also emits each block as a `HeaderOnlyBlock`, with only the block and chunk headers,
+/// to the file or named pipe at `path`.
+pub fn enable_header_only_output(path: &Path, encoding: codec::HashEncoding) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut output = Output::new(file);
+    output.set_hash_encoding(encoding);
+    let _ = HEADER_ONLY_OUTPUT.set(Mutex::new(output));
+
+    Ok(())
+}
+
+/// Encoding of the hashes of `FIRE` lines and block logs, hex by default.
+pub fn set_hash_encoding(encoding: codec::HashEncoding) {
+    output().set_hash_encoding(encoding);
+}
+
+/// Emits blocks as `FIRE BLOCK_Z` lines, compressed with `compressor`, instead of
+/// `FIRE BLOCK` lines.
+pub fn enable_compression(compressor: codec::BlockCompressor) {
+    output().set_compressor(compressor);
+}
 
 pub fn on_block(block: &codec::Block) {
+    let header = block.header.as_ref().unwrap();
+    let encoding = output().hash_encoding;
+
     // FIXME: Apply stats like approach (#Height, Block Count, Total Transactions, Total Receipts, etc..)
     info!(
         target: "firehose",
-        "Block {} Shards: {}, Transactions: {}, Receipts: {}, ExecutionOutcomes: {}",
-        block,
+        "Block #{} ({}) Shards: {}, Transactions: {}, Receipts: {}, ExecutionOutcomes: {}",
+        header.height,
+        header.hash.as_ref().unwrap().display(encoding),
         block.shards.len(),
         block.shards.iter().map(|shard| if let Some(chunk) = &shard.chunk { chunk.transactions.len() } else { 0usize }).sum::<usize>(),
         block.shards.iter().map(|shard| if let Some(chunk) = &shard.chunk { chunk.receipts.len() } else { 0usize }).sum::<usize>(),
@@ -17,17 +213,35 @@ pub fn on_block(block: &codec::Block) {
 }
 
 fn record_block(block: &codec::Block) {
-    let bytes = prost::Message::encode_to_vec(block);
     let header = block.header.as_ref().unwrap();
 
-    println!(
-        "FIRE BLOCK {height:} {hash:} {parent_height:} {parent_hash:} {lib:} {timestamp:} {hex:}",
-        height = header.height,
-        hash = header.hash.as_ref().unwrap(),
-        parent_height = header.prev_height,
-        parent_hash = header.prev_hash.as_ref().unwrap(),
-        lib = header.last_final_block.as_ref().unwrap(),
-        timestamp = header.timestamp,
-        hex = hex::encode(bytes),
-    );
+    if let Err(err) = output().write_block(header, block) {
+        error!(target: "main", "Failed to write to standard output: {}", err);
+        std::process::exit(1);
+    }
+
+    if let Some(header_only_output) = HEADER_ONLY_OUTPUT.get() {
+        let header_only_block = codec::HeaderOnlyBlock {
+            header: block.header.clone(),
+            chunk_headers: block.chunk_headers.clone(),
+        };
+
+        let mut header_only_output = header_only_output.lock().unwrap();
+        if let Err(err) = header_only_output.write_block(header, &header_only_block) {
+            error!(target: "main", "Failed to write header only block: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Emits a `FIRE UNDO` marker telling consumers that the violating block forks off the
+/// last emitted block, to standard output and the header only output.
+pub fn on_undo(violation: &Violation) {
+    record_marker(Marker::Undo(violation));
+}
+
+/// Emits a `FIRE SKIP` marker for a height that has no block, to standard output and the
+/// header only output.
+pub fn on_skipped_height(height: u64) {
+    record_marker(Marker::Skip(height));
 }
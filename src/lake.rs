@@ -0,0 +1,39 @@
+use near_indexer::StreamerMessage;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Writes each block in the same layout as NEAR Lake, that is a `<height>/block.json`
+/// file holding the `BlockView` and one `<height>/shard_<id>.json` file per shard.
+/// The height folder is zero padded to 12 digits so that lexical order matches
+/// height order.
+pub(crate) struct LakeWriter {
+    root: PathBuf,
+}
+
+impl LakeWriter {
+    pub fn new(root: PathBuf) -> Self {
+        LakeWriter { root }
+    }
+
+    /// Shard files are written before `block.json` so that a consumer seeing the
+    /// block file can rely on all shard files of the height being present.
+    pub fn write(&self, sm: &StreamerMessage) -> std::io::Result<()> {
+        let dir = self.root.join(format!("{:0>12}", sm.block.header.height));
+        std::fs::create_dir_all(&dir)?;
+
+        for shard in &sm.shards {
+            write_json(&dir.join(format!("shard_{}.json", shard.shard_id)), shard)?;
+        }
+        write_json(&dir.join("block.json"), &sm.block)?;
+
+        debug!(target: "lake", "Wrote block #{} to {}", sm.block.header.height, dir.display());
+        Ok(())
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> std::io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(value)?)?;
+    std::fs::rename(&tmp, path)
+}
@@ -0,0 +1,752 @@
+use crate::codec;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+type Hash = [u8; 32];
+
+/// Leading bytes of lineage state files, followed by the little endian format version.
+const MAGIC: &[u8; 8] = b"NEARLNG\0";
+const VERSION: u32 = 1;
+
+/// Receipt ID, transaction hash, little endian depth and height.
+const RECORD_SIZE: usize = 32 + 32 + 4 + 8;
+
+/// Smallest pending transaction record, with no result receipt and empty value, signer
+/// and receiver.
+const MIN_TRANSACTION_SIZE: usize = 32 + 8 + 4 + 4 + 4 + 1 + 3 * 4;
+
+#[derive(Clone, Copy)]
+struct Lineage {
+    transaction_hash: Hash,
+    depth: u32,
+    height: u64,
+}
+
+/// Transaction with receipts still to be executed.
+struct PendingTransaction {
+    signer_id: String,
+    receiver_id: String,
+    height: u64,
+    pending: u32,
+    receipt_count: u32,
+    /// Receipt whose outcome decides the transaction result, `None` once decided.
+    result_receipt: Option<Hash>,
+    status: codec::ReceiptStatus,
+    value: Vec<u8>,
+}
+
+impl PendingTransaction {
+    /// Follows the `SuccessReceiptId` chain of the transaction result.
+    fn resolve(&mut self, outcome: &codec::ExecutionOutcome) {
+        use codec::execution_outcome::Status;
+
+        self.result_receipt = None;
+        match &outcome.status {
+            Some(Status::SuccessReceiptId(next)) => {
+                self.result_receipt = hash(&next.id);
+            }
+            Some(Status::SuccessValue(value)) => {
+                self.status = codec::ReceiptStatus::Success;
+                self.value = value.value.clone();
+            }
+            Some(Status::Failure(_)) => self.status = codec::ReceiptStatus::Failure,
+            Some(Status::Unknown(_)) | None => self.status = codec::ReceiptStatus::Unknown,
+        }
+    }
+
+    /// `shard_id` is the shard that executed the last receipt of the transaction, or
+    /// that included it when it spawned none.
+    fn finalize(self, transaction_hash: Hash, shard_id: u64) -> codec::TransactionFinalized {
+        codec::TransactionFinalized {
+            transaction_hash: Some(codec::CryptoHash {
+                bytes: transaction_hash.to_vec(),
+            }),
+            signer_id: self.signer_id,
+            receiver_id: self.receiver_id,
+            status: self.status as i32,
+            value: self.value,
+            included_height: self.height,
+            receipt_count: self.receipt_count,
+            shard_id,
+            value_trimmed: None,
+        }
+    }
+}
+
+/// Links receipt execution outcomes to the transaction they descend from.
+///
+/// Receipts created by a transaction, and then those created by each executed receipt,
+/// are remembered until they are executed, possibly blocks later. Once none is left, the
+/// transaction is reported as finalized. Receipts and transactions still pending after
+/// the retention period are forgotten.
+///
+/// When a state file is set, the pending receipts and transactions are checkpointed to
+/// it once the block is emitted, every `checkpoint_interval` blocks, and restored on
+/// start, so that lineage survives restarts. Receipts created between the checkpoint
+/// and the block the indexer resumes from have no lineage.
+pub(crate) struct LineageTracker {
+    receipts: HashMap<Hash, Lineage>,
+    transactions: HashMap<Hash, PendingTransaction>,
+    retention: u64,
+    state_path: Option<PathBuf>,
+    checkpoint_interval: u64,
+    /// Height of the last annotated block, or of the restored checkpoint.
+    height: Option<u64>,
+    checkpoint_height: Option<u64>,
+    /// Height of the restored checkpoint until the first block is annotated.
+    restored_height: Option<u64>,
+}
+
+impl LineageTracker {
+    pub fn new(
+        retention: u64,
+        state_path: Option<PathBuf>,
+        checkpoint_interval: u64,
+    ) -> io::Result<Self> {
+        let (height, receipts, transactions) = match &state_path {
+            Some(path) => load(path)?,
+            None => (None, HashMap::new(), HashMap::new()),
+        };
+        if let Some(height) = height {
+            info!(
+                target: "main",
+                "Restored lineage of {} pending receipts and {} pending transactions at block #{}",
+                receipts.len(),
+                transactions.len(),
+                height
+            );
+        }
+
+        Ok(LineageTracker {
+            receipts,
+            transactions,
+            retention,
+            state_path,
+            checkpoint_interval: checkpoint_interval.max(1),
+            height,
+            checkpoint_height: height,
+            restored_height: height,
+        })
+    }
+
+    /// Sets the transaction hash and receipt depth of the block receipt execution
+    /// outcomes whose lineage is known, and the transactions finalized in the block.
+    /// It must see every block, unfiltered.
+    pub fn annotate(&mut self, block: &mut codec::Block) {
+        let header = block.header.as_ref().unwrap();
+        let height = header.height;
+        if let Some(last) = self.height.filter(|last| height <= *last) {
+            warn!(
+                target: "main",
+                "Block #{} was already tracked up to #{}, its lineage may be incomplete",
+                height,
+                last
+            );
+        } else if let Some(restored) = self.restored_height {
+            if header.prev_height > restored {
+                warn!(
+                    target: "main",
+                    "Lineage state stops at block #{}, receipts created up to block #{} have no lineage",
+                    restored,
+                    header.prev_height
+                );
+            }
+        }
+        self.restored_height = None;
+        self.height = Some(height);
+        let mut finalized = vec![];
+
+        for (shard_id, tx) in block.shards.iter().flat_map(|shard| {
+            shard
+                .chunk
+                .iter()
+                .flat_map(|chunk| chunk.transactions.iter())
+                .map(move |tx| (shard.shard_id, tx))
+        }) {
+            let Some(signed) = tx.transaction.as_ref() else {
+                continue;
+            };
+            let Some(transaction_hash) = hash(&signed.hash) else {
+                continue;
+            };
+
+            let outcome = tx
+                .outcome
+                .as_ref()
+                .and_then(|o| o.execution_outcome.as_ref());
+            let mut pending = PendingTransaction {
+                signer_id: signed.signer_id.clone(),
+                receiver_id: signed.receiver_id.clone(),
+                height,
+                pending: 0,
+                receipt_count: 0,
+                result_receipt: None,
+                status: codec::ReceiptStatus::Unknown,
+                value: vec![],
+            };
+            if let Some(outcome) = outcome.and_then(|o| o.outcome.as_ref()) {
+                pending.resolve(outcome);
+            }
+
+            for receipt_id in receipt_ids(outcome) {
+                pending.pending += 1;
+                pending.receipt_count += 1;
+                self.receipts.insert(
+                    receipt_id,
+                    Lineage {
+                        transaction_hash,
+                        depth: 1,
+                        height,
+                    },
+                );
+            }
+
+            if pending.pending == 0 {
+                finalized.push(pending.finalize(transaction_hash, shard_id));
+            } else {
+                self.transactions.insert(transaction_hash, pending);
+            }
+        }
+
+        for (shard_id, outcome) in block.shards.iter_mut().flat_map(|shard| {
+            let shard_id = shard.shard_id;
+            shard
+                .receipt_execution_outcomes
+                .iter_mut()
+                .map(move |outcome| (shard_id, outcome))
+        }) {
+            let Some(receipt_id) = outcome.execution_outcome.as_ref().and_then(|o| hash(&o.id))
+            else {
+                continue;
+            };
+            let Some(lineage) = self.receipts.remove(&receipt_id) else {
+                continue;
+            };
+
+            outcome.transaction_hash = Some(codec::CryptoHash {
+                bytes: lineage.transaction_hash.to_vec(),
+            });
+            outcome.receipt_depth = lineage.depth;
+
+            let mut children = 0;
+            for child_id in receipt_ids(outcome.execution_outcome.as_ref()) {
+                children += 1;
+                self.receipts.insert(
+                    child_id,
+                    Lineage {
+                        transaction_hash: lineage.transaction_hash,
+                        depth: lineage.depth + 1,
+                        height,
+                    },
+                );
+            }
+
+            let Some(tx) = self.transactions.get_mut(&lineage.transaction_hash) else {
+                continue;
+            };
+            tx.pending = tx.pending.saturating_sub(1) + children;
+            tx.receipt_count += children;
+            if tx.result_receipt == Some(receipt_id) {
+                if let Some(outcome) = outcome
+                    .execution_outcome
+                    .as_ref()
+                    .and_then(|o| o.outcome.as_ref())
+                {
+                    tx.resolve(outcome);
+                }
+            }
+            if tx.pending == 0 {
+                let tx = self.transactions.remove(&lineage.transaction_hash).unwrap();
+                finalized.push(tx.finalize(lineage.transaction_hash, shard_id));
+            }
+        }
+        block.finalized_transactions = finalized;
+
+        let retention = self.retention;
+        self.receipts
+            .retain(|_, lineage| lineage.height + retention >= height);
+        self.transactions
+            .retain(|_, tx| tx.height + retention >= height);
+    }
+
+    /// Forgets the receipts and transactions of blocks above `height`, to be called
+    /// when a `FIRE UNDO` marker rewinds the chain to the block at `height`, so that
+    /// receipts of the abandoned branch are not linked to blocks of the new one.
+    /// Receipts the abandoned branch executed are not restored, their transactions are
+    /// forgotten once the retention period is over.
+    pub fn rewind(&mut self, height: u64) {
+        self.receipts.retain(|_, lineage| lineage.height <= height);
+        self.transactions.retain(|_, tx| tx.height <= height);
+        self.height = self.height.map(|last| last.min(height));
+        self.checkpoint_height = self.checkpoint_height.map(|last| last.min(height));
+    }
+
+    /// Saves the state when a checkpoint is due, to be called once the annotated block
+    /// has been emitted so that a restart never resumes from a state ahead of the output.
+    pub fn checkpoint(&mut self) -> io::Result<()> {
+        let (Some(path), Some(height)) = (&self.state_path, self.height) else {
+            return Ok(());
+        };
+        if let Some(checkpoint) = self.checkpoint_height {
+            if height < checkpoint + self.checkpoint_interval {
+                return Ok(());
+            }
+        }
+
+        save(path, height, &self.receipts, &self.transactions)?;
+        self.checkpoint_height = Some(height);
+        Ok(())
+    }
+}
+
+fn hash(h: &Option<codec::CryptoHash>) -> Option<Hash> {
+    h.as_ref()
+        .and_then(|h| Hash::try_from(h.bytes.as_slice()).ok())
+}
+
+fn receipt_ids(outcome: Option<&codec::ExecutionOutcomeWithId>) -> impl Iterator<Item = Hash> + '_ {
+    outcome
+        .and_then(|o| o.outcome.as_ref())
+        .into_iter()
+        .flat_map(|o| o.receipt_ids.iter())
+        .filter_map(|id| Hash::try_from(id.bytes.as_slice()).ok())
+}
+
+/// Reads fixed size fields off the lineage state.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "lineage state is truncated",
+            ));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn hash(&mut self) -> io::Result<Hash> {
+        Ok(Hash::try_from(self.take(32)?).unwrap())
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn bytes(&mut self) -> io::Result<Vec<u8>> {
+        let len = self.u32()? as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        String::from_utf8(self.bytes()?).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+type State = (
+    Option<u64>,
+    HashMap<Hash, Lineage>,
+    HashMap<Hash, PendingTransaction>,
+);
+
+/// Loads the state, made of the magic bytes, format version and height of the last
+/// tracked block, the count of pending receipts followed by their fixed size records,
+/// then the count of pending transactions followed by theirs.
+fn load(path: &Path) -> io::Result<State> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            return Ok((None, HashMap::new(), HashMap::new()))
+        }
+        Err(err) => return Err(err),
+    };
+    let mut reader = Reader { bytes: &bytes };
+
+    if reader.take(MAGIC.len()).ok() != Some(MAGIC.as_slice()) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("{:?} is not a lineage state file", path),
+        ));
+    }
+    let version = reader.u32()?;
+    if version != VERSION {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "lineage state {:?} has unsupported version {}",
+                path, version
+            ),
+        ));
+    }
+    let height = reader.u64()?;
+
+    let count = reader.u64()?;
+    let len = usize::try_from(count)
+        .ok()
+        .and_then(|count| count.checked_mul(RECORD_SIZE))
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("lineage state {:?} has {} receipts", path, count),
+            )
+        })?;
+    let records = reader.take(len)?;
+    let receipts = records
+        .chunks_exact(RECORD_SIZE)
+        .map(|record| {
+            let (receipt_id, rest) = record.split_at(32);
+            let (transaction_hash, rest) = rest.split_at(32);
+            let (depth, height) = rest.split_at(4);
+
+            (
+                Hash::try_from(receipt_id).unwrap(),
+                Lineage {
+                    transaction_hash: Hash::try_from(transaction_hash).unwrap(),
+                    depth: u32::from_le_bytes(depth.try_into().unwrap()),
+                    height: u64::from_le_bytes(height.try_into().unwrap()),
+                },
+            )
+        })
+        .collect();
+
+    let count = reader.u64()?;
+    if count > (reader.bytes.len() / MIN_TRANSACTION_SIZE) as u64 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("lineage state {:?} has {} transactions", path, count),
+        ));
+    }
+    let mut transactions = HashMap::with_capacity(count as usize);
+    for _ in 0..count {
+        let transaction_hash = reader.hash()?;
+        let height = reader.u64()?;
+        let pending = reader.u32()?;
+        let receipt_count = reader.u32()?;
+        let status = codec::ReceiptStatus::from_i32(reader.u32()? as i32)
+            .unwrap_or(codec::ReceiptStatus::Unknown);
+        let result_receipt = match reader.take(1)?[0] {
+            0 => None,
+            _ => Some(reader.hash()?),
+        };
+        transactions.insert(
+            transaction_hash,
+            PendingTransaction {
+                value: reader.bytes()?,
+                signer_id: reader.string()?,
+                receiver_id: reader.string()?,
+                height,
+                pending,
+                receipt_count,
+                result_receipt,
+                status,
+            },
+        );
+    }
+    if !reader.bytes.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("lineage state {:?} has trailing bytes", path),
+        ));
+    }
+
+    Ok((Some(height), receipts, transactions))
+}
+
+/// Writes the state to a temporary file synced to disk before replacing the previous
+/// one, then syncs the directory so that the rename itself survives a crash.
+fn save(
+    path: &Path,
+    height: u64,
+    receipts: &HashMap<Hash, Lineage>,
+    transactions: &HashMap<Hash, PendingTransaction>,
+) -> io::Result<()> {
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 20 + receipts.len() * RECORD_SIZE);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&height.to_le_bytes());
+    bytes.extend_from_slice(&(receipts.len() as u64).to_le_bytes());
+    for (receipt_id, lineage) in receipts {
+        bytes.extend_from_slice(receipt_id);
+        bytes.extend_from_slice(&lineage.transaction_hash);
+        bytes.extend_from_slice(&lineage.depth.to_le_bytes());
+        bytes.extend_from_slice(&lineage.height.to_le_bytes());
+    }
+
+    bytes.extend_from_slice(&(transactions.len() as u64).to_le_bytes());
+    for (transaction_hash, tx) in transactions {
+        bytes.extend_from_slice(transaction_hash);
+        bytes.extend_from_slice(&tx.height.to_le_bytes());
+        bytes.extend_from_slice(&tx.pending.to_le_bytes());
+        bytes.extend_from_slice(&tx.receipt_count.to_le_bytes());
+        bytes.extend_from_slice(&(tx.status as i32 as u32).to_le_bytes());
+        match &tx.result_receipt {
+            Some(receipt_id) => {
+                bytes.push(1);
+                bytes.extend_from_slice(receipt_id);
+            }
+            None => bytes.push(0),
+        }
+        write_bytes(&mut bytes, &tx.value);
+        write_bytes(&mut bytes, tx.signer_id.as_bytes());
+        write_bytes(&mut bytes, tx.receiver_id.as_bytes());
+    }
+
+    let tmp = path.with_extension("tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(&bytes)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+
+    match path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        Some(dir) => File::open(dir)?.sync_all(),
+        None => File::open(".")?.sync_all(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::execution_outcome::Status;
+
+    fn crypto_hash(byte: u8) -> Option<codec::CryptoHash> {
+        Some(codec::CryptoHash {
+            bytes: vec![byte; 32],
+        })
+    }
+
+    fn outcome(
+        id: u8,
+        receipt_ids: &[u8],
+        status: Status,
+    ) -> Option<codec::ExecutionOutcomeWithId> {
+        Some(codec::ExecutionOutcomeWithId {
+            id: crypto_hash(id),
+            outcome: Some(codec::ExecutionOutcome {
+                receipt_ids: receipt_ids
+                    .iter()
+                    .map(|id| crypto_hash(*id).unwrap())
+                    .collect(),
+                status: Some(status),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    fn receipt_id_status(id: u8) -> Status {
+        Status::SuccessReceiptId(codec::SuccessReceiptIdExecutionStatus {
+            id: crypto_hash(id),
+        })
+    }
+
+    fn block(
+        height: u64,
+        transactions: Vec<codec::IndexerTransactionWithOutcome>,
+        outcomes: Vec<Option<codec::ExecutionOutcomeWithId>>,
+    ) -> codec::Block {
+        codec::Block {
+            header: Some(codec::BlockHeader {
+                height,
+                prev_height: height - 1,
+                ..Default::default()
+            }),
+            shards: vec![codec::IndexerShard {
+                shard_id: 3,
+                chunk: Some(codec::IndexerChunk {
+                    transactions,
+                    ..Default::default()
+                }),
+                receipt_execution_outcomes: outcomes
+                    .into_iter()
+                    .map(
+                        |execution_outcome| codec::IndexerExecutionOutcomeWithReceipt {
+                            execution_outcome,
+                            ..Default::default()
+                        },
+                    )
+                    .collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// Transaction `hash` creating the receipt `receipt_id`.
+    fn transaction(hash: u8, receipt_id: u8) -> codec::IndexerTransactionWithOutcome {
+        codec::IndexerTransactionWithOutcome {
+            transaction: Some(codec::SignedTransaction {
+                signer_id: "alice.near".to_string(),
+                receiver_id: "contract.near".to_string(),
+                hash: crypto_hash(hash),
+                ..Default::default()
+            }),
+            outcome: Some(codec::IndexerExecutionOutcomeWithOptionalReceipt {
+                execution_outcome: outcome(hash, &[receipt_id], receipt_id_status(receipt_id)),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn state_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("lineage-{}-{}", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn finalizes_transaction_once_its_receipts_are_executed() {
+        let mut tracker = LineageTracker::new(100, None, 1).unwrap();
+
+        let mut first = block(10, vec![transaction(1, 2)], vec![]);
+        tracker.annotate(&mut first);
+        assert!(first.finalized_transactions.is_empty());
+
+        let mut second = block(11, vec![], vec![outcome(2, &[3, 4], receipt_id_status(3))]);
+        tracker.annotate(&mut second);
+        let executed = &second.shards[0].receipt_execution_outcomes[0];
+        assert_eq!(executed.transaction_hash, crypto_hash(1));
+        assert_eq!(executed.receipt_depth, 1);
+        assert!(second.finalized_transactions.is_empty());
+
+        let value = Status::SuccessValue(codec::SuccessValueExecutionStatus {
+            value: b"\"ok\"".to_vec(),
+            ..Default::default()
+        });
+        let refund = Status::SuccessValue(Default::default());
+        let mut third = block(
+            12,
+            vec![],
+            vec![outcome(3, &[], value), outcome(4, &[], refund)],
+        );
+        tracker.annotate(&mut third);
+        let depths: Vec<u32> = third.shards[0]
+            .receipt_execution_outcomes
+            .iter()
+            .map(|outcome| outcome.receipt_depth)
+            .collect();
+        assert_eq!(depths, vec![2, 2]);
+        assert_eq!(
+            third.finalized_transactions,
+            vec![codec::TransactionFinalized {
+                transaction_hash: crypto_hash(1),
+                signer_id: "alice.near".to_string(),
+                receiver_id: "contract.near".to_string(),
+                status: codec::ReceiptStatus::Success as i32,
+                value: b"\"ok\"".to_vec(),
+                included_height: 10,
+                receipt_count: 3,
+                shard_id: 3,
+                value_trimmed: None,
+            }]
+        );
+        assert!(tracker.receipts.is_empty());
+        assert!(tracker.transactions.is_empty());
+    }
+
+    #[test]
+    fn state_round_trips() {
+        let path = state_path("round-trip");
+        let mut tracker = LineageTracker::new(100, Some(path.clone()), 1).unwrap();
+        tracker.annotate(&mut block(
+            10,
+            vec![transaction(1, 2), transaction(5, 6)],
+            vec![],
+        ));
+        tracker.annotate(&mut block(
+            11,
+            vec![],
+            vec![outcome(2, &[3], receipt_id_status(3))],
+        ));
+        tracker.checkpoint().unwrap();
+
+        let mut restored = LineageTracker::new(100, Some(path.clone()), 1).unwrap();
+        assert_eq!(restored.height, Some(11));
+        assert_eq!(restored.receipts.len(), 2);
+        assert_eq!(restored.transactions.len(), 2);
+        let tx = &restored.transactions[&[1; 32]];
+        assert_eq!(
+            (
+                tx.signer_id.as_str(),
+                tx.height,
+                tx.pending,
+                tx.receipt_count
+            ),
+            ("alice.near", 10, 1, 2)
+        );
+        assert_eq!(tx.result_receipt, Some([3; 32]));
+
+        let mut next = block(
+            12,
+            vec![],
+            vec![outcome(3, &[], Status::SuccessValue(Default::default()))],
+        );
+        restored.annotate(&mut next);
+        assert_eq!(
+            next.shards[0].receipt_execution_outcomes[0].receipt_depth,
+            2
+        );
+        assert_eq!(next.finalized_transactions.len(), 1);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_corrupted_state() {
+        let path = state_path("corrupted");
+        let header = [&MAGIC[..], &VERSION.to_le_bytes(), &10u64.to_le_bytes()].concat();
+
+        for counts in [
+            [&u64::MAX.to_le_bytes()[..]].concat(),
+            [&(u64::MAX / RECORD_SIZE as u64).to_le_bytes()[..]].concat(),
+            [&0u64.to_le_bytes()[..], &u64::MAX.to_le_bytes()].concat(),
+            [&0u64.to_le_bytes()[..], &1u64.to_le_bytes()].concat(),
+        ] {
+            fs::write(&path, [&header[..], &counts].concat()).unwrap();
+            assert_eq!(
+                LineageTracker::new(100, Some(path.clone()), 1)
+                    .err()
+                    .map(|err| err.kind()),
+                Some(ErrorKind::InvalidData)
+            );
+        }
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rewind_forgets_receipts_of_undone_blocks() {
+        let mut tracker = LineageTracker::new(100, None, 1).unwrap();
+        tracker.annotate(&mut block(10, vec![transaction(1, 2)], vec![]));
+        tracker.annotate(&mut block(11, vec![transaction(5, 6)], vec![]));
+
+        tracker.rewind(10);
+        assert_eq!(tracker.receipts.keys().collect::<Vec<_>>(), vec![&[2; 32]]);
+        assert_eq!(
+            tracker.transactions.keys().collect::<Vec<_>>(),
+            vec![&[1; 32]]
+        );
+
+        // The block replacing the undone one has no lineage for its receipts.
+        let mut replacing = block(11, vec![], vec![outcome(6, &[], receipt_id_status(7))]);
+        tracker.annotate(&mut replacing);
+        assert_eq!(
+            replacing.shards[0].receipt_execution_outcomes[0].transaction_hash,
+            None
+        );
+    }
+}
@@ -2,7 +2,7 @@ use tracing_subscriber::EnvFilter;
 
 pub(crate) fn init() {
     let config = std::env::var("RUST_LOG").unwrap_or(
-        "main=info,near=info,stats=info,firehose=info,indexer=info,network=info,runtime=info"
+        "main=info,near=info,stats=info,firehose=info,lake=info,indexer=info,network=info,runtime=info"
             .to_string(),
     );
     let env_filter = EnvFilter::new(config);
@@ -1,13 +1,30 @@
+mod approvals;
+mod args;
+mod balances;
 mod codec;
 mod configs;
+mod continuity;
+mod contracts;
+mod decode;
+mod derived;
+mod events;
+mod filter;
 mod firehose;
+mod lake;
+mod lineage;
 mod logging;
+mod pipeline;
+mod shards;
+mod skips;
+mod tokens;
+mod trim;
+mod verify;
 
 use actix;
 use clap::Parser;
 use configs::{Opts, SubCommand};
 use near_indexer;
-use tracing::info;
+use tracing::{error, info};
 
 fn main() {
     info!(target: "main", "Starting");
@@ -17,8 +34,16 @@ fn main() {
 
     let opts: Opts = Opts::parse();
 
+    let compression_dictionary = opts.compression_dictionary.as_ref().map(|path| {
+        std::fs::read(path).unwrap_or_else(|err| {
+            error!(target: "main", "Failed to read compression dictionary {:?}: {}", path, err);
+            std::process::exit(1);
+        })
+    });
+
     let home_dir = opts
         .home
+        .clone()
         .unwrap_or(std::path::PathBuf::from(near_indexer::get_default_home()));
 
     let mut sync_mode = near_indexer::SyncModeEnum::FromInterruption;
@@ -30,6 +55,26 @@ fn main() {
     match opts.subcmd {
         SubCommand::Run => {
             info!(target: "main", "Running");
+            let hash_encoding = opts.hash_encoding.unwrap_or(codec::HashEncoding::Hex);
+            firehose::set_hash_encoding(hash_encoding);
+            if opts.compress_blocks {
+                let compressor = codec::BlockCompressor::new(
+                    opts.compression_level,
+                    compression_dictionary.as_deref(),
+                )
+                .unwrap_or_else(|err| {
+                    error!(target: "main", "Failed to initiate block compression: {}", err);
+                    std::process::exit(1);
+                });
+                firehose::enable_compression(compressor);
+            }
+            if let Some(path) = &opts.header_only_output {
+                if let Err(err) = firehose::enable_header_only_output(path, hash_encoding) {
+                    error!(target: "main", "Failed to open header only output {:?}: {}", path, err);
+                    std::process::exit(1);
+                }
+            }
+
             let indexer_config = near_indexer::IndexerConfig {
                 home_dir,
                 sync_mode,
@@ -46,18 +91,42 @@ fn main() {
                     near_indexer::Indexer::new(indexer_config).expect("Failed to initiate Indexer");
                 info!(target: "main", "Indexer initiated");
 
+                let (view_client, _) = indexer.client_actors();
+                let mut pipeline = pipeline::Pipeline::new(&opts, view_client);
+
                 let mut stream = indexer.streamer();
                 info!(target: "main", "Streamer initiated");
 
                 actix::spawn(async move {
                     while let Some(streamer_message) = stream.recv().await {
-                        let block = codec::Block::from(&streamer_message);
-                        firehose::on_block(&block);
+                        pipeline.process(streamer_message).await;
                     }
                 });
             });
 
             system.run().unwrap();
         }
+        SubCommand::Decode => {
+            let hash_encoding = opts.hash_encoding.unwrap_or(codec::HashEncoding::Base58);
+            if let Err(err) = decode::run(compression_dictionary.as_deref(), hash_encoding) {
+                error!(target: "main", "Failed to decode blocks: {}", err);
+                std::process::exit(1);
+            }
+        }
+        SubCommand::ContractCode { hash } => {
+            let Some(dir) = &opts.contract_code_store else {
+                error!(target: "main", "--contract-code-store is required");
+                std::process::exit(1);
+            };
+
+            let code = contracts::read(dir, &hash).unwrap_or_else(|err| {
+                error!(target: "main", "Failed to read contract code {}: {}", hash, err);
+                std::process::exit(1);
+            });
+            if let Err(err) = std::io::Write::write_all(&mut std::io::stdout().lock(), &code) {
+                error!(target: "main", "Failed to write contract code: {}", err);
+                std::process::exit(1);
+            }
+        }
     }
 }
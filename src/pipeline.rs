@@ -0,0 +1,322 @@
+use crate::approvals::ApprovalResolver;
+use crate::args::ArgsDecoder;
+use crate::balances::BalanceTracker;
+use crate::codec;
+use crate::configs::Opts;
+use crate::continuity::{ContinuityGuard, ContinuityPolicy, GapPolicy, Violation};
+use crate::contracts::CodeStore;
+use crate::derived;
+use crate::filter::{AccountPattern, BlockFilter};
+use crate::firehose::{self, Marker};
+use crate::lake::LakeWriter;
+use crate::lineage::LineageTracker;
+use crate::shards::ShardOutputs;
+use crate::skips::SkippedHeights;
+use crate::trim::PayloadTrimmer;
+use crate::verify::outcomes::OutcomeVerifier;
+use crate::verify::roots::RootVerifier;
+use crate::verify::{Failure, VerificationPolicy};
+use near_client::ViewClientActor;
+use near_indexer::StreamerMessage;
+use tracing::{error, warn};
+
+/// Turns each message received from the NEAR indexer streamer into the configured
+/// outputs.
+pub(crate) struct Pipeline {
+    approval_resolver: Option<ApprovalResolver>,
+    lineage_tracker: Option<LineageTracker>,
+    balance_tracker: Option<BalanceTracker>,
+    emit_state_changes: bool,
+    lake_writer: Option<LakeWriter>,
+    continuity_policy: ContinuityPolicy,
+    gap_policy: GapPolicy,
+    continuity_guard: ContinuityGuard,
+    emit_skipped_heights: bool,
+    skipped_heights: SkippedHeights,
+    outcomes_policy: VerificationPolicy,
+    outcome_verifier: OutcomeVerifier,
+    roots_policy: VerificationPolicy,
+    root_verifier: RootVerifier,
+    verification_failures: u64,
+    block_filter: Option<BlockFilter>,
+    legacy_ft_contracts: Vec<AccountPattern>,
+    shard_outputs: Option<ShardOutputs>,
+    args_decoder: Option<ArgsDecoder>,
+    payload_trimmer: Option<PayloadTrimmer>,
+    code_store: Option<CodeStore>,
+}
+
+impl Pipeline {
+    pub fn new(opts: &Opts, view_client: actix::Addr<ViewClientActor>) -> Self {
+        let (outcomes_policy, roots_policy) = match opts.paranoid {
+            true => (VerificationPolicy::Reject, VerificationPolicy::Reject),
+            false => (opts.verify_outcomes, opts.verify_roots),
+        };
+
+        Pipeline {
+            approval_resolver: match opts.resolve_approvals {
+                true => Some(ApprovalResolver::new(view_client.clone())),
+                false => None,
+            },
+            lineage_tracker: match opts.track_lineage {
+                true => Some(
+                    LineageTracker::new(
+                        opts.lineage_retention_blocks,
+                        opts.lineage_state.clone(),
+                        opts.lineage_checkpoint_blocks,
+                    )
+                    .unwrap_or_else(|err| {
+                        error!(target: "main", "Failed to restore lineage state: {}", err);
+                        std::process::exit(1);
+                    }),
+                ),
+                false => None,
+            },
+            balance_tracker: match opts.balance_changes {
+                true => Some(BalanceTracker::new(view_client.clone())),
+                false => None,
+            },
+            emit_state_changes: opts.state_changes,
+            lake_writer: opts.lake_output_dir.clone().map(LakeWriter::new),
+            continuity_policy: opts.continuity_policy,
+            gap_policy: opts.gap_policy,
+            continuity_guard: ContinuityGuard::new(),
+            emit_skipped_heights: opts.emit_skipped_heights,
+            skipped_heights: SkippedHeights::new(),
+            outcomes_policy,
+            outcome_verifier: OutcomeVerifier::new(),
+            roots_policy,
+            root_verifier: RootVerifier::new(view_client),
+            verification_failures: 0,
+            block_filter: match opts.filters.is_empty() && opts.shards.is_empty() {
+                true => None,
+                false => Some(BlockFilter::new(opts.filters.clone(), opts.shards.clone())),
+            },
+            legacy_ft_contracts: opts.legacy_ft_contracts.clone(),
+            shard_outputs: opts.shard_output_dir.clone().map(|dir| {
+                let hash_encoding = opts.hash_encoding.unwrap_or(codec::HashEncoding::Hex);
+                ShardOutputs::new(dir, opts.filters.clone(), hash_encoding)
+            }),
+            args_decoder: match opts.decode_args {
+                true => Some(ArgsDecoder::new(&opts.abis).unwrap_or_else(|err| {
+                    error!(target: "main", "Failed to read contract ABI {}", err);
+                    std::process::exit(1);
+                })),
+                false => None,
+            },
+            payload_trimmer: opts.trim_config.as_ref().map(|path| {
+                PayloadTrimmer::from_file(path).unwrap_or_else(|err| {
+                    error!(target: "main", "Failed to read trim config {:?}: {}", path, err);
+                    std::process::exit(1);
+                })
+            }),
+            code_store: opts.contract_code_store.clone().map(|dir| {
+                CodeStore::new(dir).unwrap_or_else(|err| {
+                    error!(target: "main", "Failed to open contract code store: {}", err);
+                    std::process::exit(1);
+                })
+            }),
+        }
+    }
+
+    pub async fn process(&mut self, streamer_message: StreamerMessage) {
+        // Checks that may stop the indexer run before anything is written for the block.
+        let undo = self.check_continuity(&streamer_message);
+
+        let height = streamer_message.block.header.height;
+        let prev_hash = streamer_message.block.header.prev_hash;
+        if self.outcomes_policy != VerificationPolicy::Off {
+            let failures = self.outcome_verifier.verify(&streamer_message);
+            self.on_verification_failures(self.outcomes_policy, height, failures);
+        }
+
+        if self.roots_policy != VerificationPolicy::Off {
+            let failures = self.root_verifier.verify(&streamer_message).await;
+            self.on_verification_failures(self.roots_policy, height, failures);
+        }
+
+        if let Some(lake_writer) = &self.lake_writer {
+            if let Err(err) = lake_writer.write(&streamer_message) {
+                error!(target: "main", "Failed to write NEAR Lake files: {}", err);
+                std::process::exit(1);
+            }
+        }
+
+        let approvers = match &mut self.approval_resolver {
+            Some(resolver) => Some(resolver.resolve(&streamer_message.block.header).await),
+            None => None,
+        };
+
+        let mut block = codec::Block::from(streamer_message);
+        let header = block.header.as_mut().unwrap();
+        match approvers {
+            Some(Ok(approvers)) => {
+                for (approval, account_id) in header.block_approvals.iter_mut().zip(approvers) {
+                    approval.account_id = account_id.to_string();
+                }
+            }
+            Some(Err(err)) => {
+                warn!(target: "firehose", "Block #{} approvals not resolved, {}", height, err);
+                header.block_approvals_error = err;
+            }
+            None => {}
+        }
+
+        if let Some(lineage_tracker) = &mut self.lineage_tracker {
+            if let Some(violation) = &undo {
+                lineage_tracker.rewind(violation.parent().height);
+            }
+            lineage_tracker.annotate(&mut block);
+        }
+
+        if let Some(balance_tracker) = &mut self.balance_tracker {
+            match balance_tracker.derive(&block, prev_hash).await {
+                Ok(changes) => block.balance_changes = changes,
+                Err(err) => {
+                    warn!(
+                        target: "firehose",
+                        "Block #{} balance changes not derived, {}",
+                        height,
+                        err
+                    );
+                    block.balance_changes_error = err;
+                }
+            }
+        }
+        if !self.emit_state_changes {
+            block.state_changes.clear();
+        }
+
+        if let Some(violation) = &undo {
+            firehose::on_undo(violation);
+            self.write_shard_marker(&Marker::Undo(violation));
+        }
+
+        let header = block.header.as_ref().unwrap();
+        let skipped = self.skipped_heights.observe(header);
+        if self.emit_skipped_heights {
+            for height in skipped {
+                firehose::on_skipped_height(height);
+                self.write_shard_marker(&Marker::Skip(height));
+            }
+        }
+
+        if let Some(block_filter) = &self.block_filter {
+            block_filter.apply(&mut block);
+        }
+        derived::derive(&mut block, &self.legacy_ft_contracts);
+
+        if let Some(payload_trimmer) = &self.payload_trimmer {
+            payload_trimmer.apply(&mut block);
+        }
+
+        if let Some(args_decoder) = &self.args_decoder {
+            args_decoder.apply(&mut block);
+        }
+
+        if let Some(code_store) = &mut self.code_store {
+            if let Err(err) = code_store.dedup(&mut block) {
+                error!(target: "main", "Failed to store contract code: {}", err);
+                std::process::exit(1);
+            }
+        }
+
+        firehose::on_block(&block);
+
+        if let Some(shard_outputs) = &mut self.shard_outputs {
+            if let Err(err) = shard_outputs.write(&block) {
+                error!(target: "main", "Failed to write per shard blocks: {}", err);
+                std::process::exit(1);
+            }
+        }
+
+        if let Some(lineage_tracker) = &mut self.lineage_tracker {
+            if let Err(err) = lineage_tracker.checkpoint() {
+                error!(target: "main", "Failed to save lineage state: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    fn write_shard_marker(&mut self, marker: &Marker) {
+        if let Some(shard_outputs) = &mut self.shard_outputs {
+            if let Err(err) = shard_outputs.write_marker(marker) {
+                error!(target: "main", "Failed to write per shard markers: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Checks that the block extends the last one, halting the indexer when the policy
+    /// says so. Returns the violation to emit a `FIRE UNDO` marker for before the block.
+    fn check_continuity(&mut self, streamer_message: &StreamerMessage) -> Option<Violation> {
+        if self.continuity_policy == ContinuityPolicy::Off && self.gap_policy == GapPolicy::Off {
+            return None;
+        }
+
+        let header = &streamer_message.block.header;
+        let violation = self.continuity_guard.check(header).err();
+        self.continuity_guard.advance(header);
+
+        let violation = violation?;
+        if let Violation::Gap { .. } = violation {
+            match self.gap_policy {
+                GapPolicy::Off => return None,
+                GapPolicy::Report => {
+                    warn!(target: "firehose", "Chain continuity violation, {}", violation);
+                    return None;
+                }
+                GapPolicy::Halt => {
+                    error!(target: "main", "Chain continuity violation, {}", violation);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        match self.continuity_policy {
+            ContinuityPolicy::Off => None,
+            ContinuityPolicy::Halt => {
+                error!(target: "main", "Chain continuity violation, {}", violation);
+                std::process::exit(1);
+            }
+            ContinuityPolicy::Undo => {
+                warn!(target: "firehose", "Chain continuity violation, {}", violation);
+                violation.requires_undo().then_some(violation)
+            }
+        }
+    }
+
+    fn on_verification_failures(
+        &mut self,
+        policy: VerificationPolicy,
+        height: u64,
+        failures: Vec<Failure>,
+    ) {
+        let (unverifiable, failures): (Vec<_>, Vec<_>) = failures
+            .into_iter()
+            .partition(|failure| matches!(failure, Failure::Unverifiable(_)));
+        for reason in &unverifiable {
+            warn!(target: "firehose", "Block #{} not verified, {}", height, reason);
+        }
+
+        for failure in &failures {
+            if policy == VerificationPolicy::Reject {
+                error!(target: "main", "Block #{} failed verification, {}", height, failure);
+                std::process::exit(1);
+            }
+
+            warn!(target: "firehose", "Block #{} failed verification, {}", height, failure);
+        }
+
+        if !failures.is_empty() {
+            self.verification_failures += failures.len() as u64;
+            warn!(
+                target: "stats",
+                "Verification failures: {} in block #{}, {} in total",
+                failures.len(),
+                height,
+                self.verification_failures,
+            );
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use crate::codec;
+use crate::filter::{BlockFilter, FilterRule};
+use crate::firehose::{Marker, Output};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Writes each block split per shard, as blocks holding a single shard, to one
+/// `shard-<id>.fire` file or named pipe per shard under a directory.
+///
+/// Each shard has its own cursor, `shard-<id>.cursor` holding the height and hash of
+/// the last block written for it, so that blocks replayed after a restart are not
+/// written twice to a shard. Blocks below the cursor are taken as replayed, as is the
+/// cursor block itself, a block at the cursor height with another hash replaces it. A
+/// `FIRE UNDO` marker rewinds the cursors to the parent of the new block.
+pub(crate) struct ShardOutputs {
+    dir: PathBuf,
+    filters: Vec<FilterRule>,
+    hash_encoding: codec::HashEncoding,
+    sinks: HashMap<u64, ShardSink>,
+}
+
+struct ShardSink {
+    output: Output<File>,
+    cursor_path: PathBuf,
+    cursor: Option<Cursor>,
+    filter_hash: Vec<u8>,
+}
+
+/// Last block written to a shard, its hash as written to the cursor file.
+struct Cursor {
+    height: u64,
+    hash: String,
+}
+
+impl Cursor {
+    fn is_replayed(&self, header: &codec::BlockHeader) -> bool {
+        header.height < self.height
+            || (header.height == self.height
+                && header.hash.as_ref().unwrap().to_string() == self.hash)
+    }
+
+    /// Writes the cursor to a temporary file synced to disk before it replaces `path`,
+    /// so that a crash leaves either cursor whole.
+    fn save(&self, path: &Path) -> io::Result<()> {
+        let tmp_path = path.with_extension("cursor.tmp");
+        let mut file = File::create(&tmp_path)?;
+        writeln!(file, "{} {}", self.height, self.hash)?;
+        file.sync_all()?;
+
+        fs::rename(&tmp_path, path)
+    }
+
+    fn load(path: &Path) -> io::Result<Option<Cursor>> {
+        let cursor = match fs::read_to_string(path) {
+            Ok(cursor) => cursor,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        let mut fields = cursor.split_whitespace();
+        match (
+            fields.next().and_then(|height| height.parse().ok()),
+            fields.next(),
+            fields.next(),
+        ) {
+            (Some(height), Some(hash), None) => Ok(Some(Cursor {
+                height,
+                hash: hash.to_string(),
+            })),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid cursor {:?} in {:?}", cursor, path),
+            )),
+        }
+    }
+}
+
+impl ShardOutputs {
+    pub fn new(dir: PathBuf, filters: Vec<FilterRule>, hash_encoding: codec::HashEncoding) -> Self {
+        ShardOutputs {
+            dir,
+            filters,
+            hash_encoding,
+            sinks: HashMap::new(),
+        }
+    }
+
+    pub fn write(&mut self, block: &codec::Block) -> io::Result<()> {
+        let header = block.header.as_ref().unwrap();
+
+        for shard in &block.shards {
+            if !self.sinks.contains_key(&shard.shard_id) {
+                let sink = self.open(shard.shard_id)?;
+                self.sinks.insert(shard.shard_id, sink);
+            }
+
+            let sink = self.sinks.get_mut(&shard.shard_id).unwrap();
+
+            if matches!(&sink.cursor, Some(cursor) if cursor.is_replayed(header)) {
+                continue;
+            }
+
+            // Derived entries are taken from the block rather than derived again, as
+            // payloads they come from may have been trimmed since.
+            let receipt_ids: HashSet<&[u8]> = shard
+                .receipt_execution_outcomes
+                .iter()
+                .filter_map(|o| o.execution_outcome.as_ref())
+                .filter_map(|o| o.id.as_ref())
+                .map(|id| id.bytes.as_slice())
+                .collect();
+            let in_shard = |id: &Option<codec::CryptoHash>| {
+                id.as_ref()
+                    .map_or(false, |id| receipt_ids.contains(id.bytes.as_slice()))
+            };
+
+            let shard_block = codec::Block {
+                author: block.author.clone(),
+                header: block.header.clone(),
+                chunk_headers: block.chunk_headers.clone(),
+                shards: vec![shard.clone()],
+                filtering_applied: true,
+                filter_hash: sink.filter_hash.clone(),
+                events: block
+                    .events
+                    .iter()
+                    .filter(|e| in_shard(&e.receipt_id))
+                    .cloned()
+                    .collect(),
+                ft_transfers: block
+                    .ft_transfers
+                    .iter()
+                    .filter(|t| in_shard(&t.receipt_id))
+                    .cloned()
+                    .collect(),
+                nft_activities: block
+                    .nft_activities
+                    .iter()
+                    .filter(|a| in_shard(&a.receipt_id))
+                    .cloned()
+                    .collect(),
+                finalized_transactions: block
+                    .finalized_transactions
+                    .iter()
+                    .filter(|tx| tx.shard_id == shard.shard_id)
+                    .cloned()
+                    .collect(),
+                state_changes: block
+                    .state_changes
+                    .iter()
+                    .filter(|change| change.shard_id == shard.shard_id)
+                    .cloned()
+                    .collect(),
+                balance_changes: block
+                    .balance_changes
+                    .iter()
+                    .filter(|change| change.shard_id == shard.shard_id)
+                    .cloned()
+                    .collect(),
+                balance_changes_error: block.balance_changes_error.clone(),
+            };
+            sink.output.write_block(header, &shard_block)?;
+
+            let cursor = Cursor {
+                height: header.height,
+                hash: header.hash.as_ref().unwrap().to_string(),
+            };
+            cursor.save(&sink.cursor_path)?;
+            sink.cursor = Some(cursor);
+        }
+
+        Ok(())
+    }
+
+    /// Writes `marker` to the file of every shard a block was written for. `FIRE UNDO`
+    /// also rewinds the cursors above the parent of the new block to that parent, so
+    /// that the new block is written even at or below the height of the undone ones.
+    pub fn write_marker(&mut self, marker: &Marker) -> io::Result<()> {
+        for sink in self.sinks.values_mut() {
+            sink.output.write_marker(marker)?;
+
+            if let Marker::Undo(violation) = marker {
+                let parent = violation.parent();
+                if matches!(&sink.cursor, Some(cursor) if cursor.height > parent.height) {
+                    let cursor = Cursor {
+                        height: parent.height,
+                        hash: parent.hash.to_string(),
+                    };
+                    cursor.save(&sink.cursor_path)?;
+                    sink.cursor = Some(cursor);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn open(&self, shard_id: u64) -> io::Result<ShardSink> {
+        fs::create_dir_all(&self.dir)?;
+
+        let cursor_path = self.dir.join(format!("shard-{}.cursor", shard_id));
+        let cursor = Cursor::load(&cursor_path)?;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(format!("shard-{}.fire", shard_id)))?;
+        let mut output = Output::new(file);
+        output.set_hash_encoding(self.hash_encoding);
+
+        Ok(ShardSink {
+            output,
+            cursor_path,
+            cursor,
+            filter_hash: BlockFilter::new(self.filters.clone(), vec![shard_id])
+                .hash()
+                .to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::continuity::{BlockRef, Violation};
+
+    fn block(height: u64, hash: u8) -> codec::Block {
+        let crypto_hash = |byte| {
+            Some(codec::CryptoHash {
+                bytes: vec![byte; 32],
+            })
+        };
+
+        codec::Block {
+            header: Some(codec::BlockHeader {
+                height,
+                hash: crypto_hash(hash),
+                prev_hash: crypto_hash(0),
+                last_final_block: crypto_hash(0),
+                ..Default::default()
+            }),
+            shards: vec![codec::IndexerShard {
+                shard_id: 0,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn block_ref(height: u64, hash: u8) -> BlockRef {
+        BlockRef {
+            height,
+            hash: codec::CryptoHash {
+                bytes: vec![hash; 32],
+            },
+        }
+    }
+
+    fn outputs(name: &str) -> (ShardOutputs, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("shards-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        (
+            ShardOutputs::new(dir.clone(), vec![], codec::HashEncoding::Hex),
+            dir,
+        )
+    }
+
+    fn written_heights(dir: &Path) -> Vec<String> {
+        fs::read_to_string(dir.join("shard-0.fire"))
+            .unwrap()
+            .lines()
+            .map(|line| line.split(' ').take(3).collect::<Vec<_>>().join(" "))
+            .collect()
+    }
+
+    #[test]
+    fn replayed_blocks_are_not_written_again() {
+        let (mut outputs, dir) = outputs("replay");
+        outputs.write(&block(10, 1)).unwrap();
+        outputs.write(&block(11, 2)).unwrap();
+
+        let mut restarted = ShardOutputs::new(dir.clone(), vec![], codec::HashEncoding::Hex);
+        restarted.write(&block(10, 1)).unwrap();
+        restarted.write(&block(11, 2)).unwrap();
+        restarted.write(&block(12, 3)).unwrap();
+
+        assert_eq!(
+            written_heights(&dir),
+            vec!["FIRE BLOCK 10", "FIRE BLOCK 11", "FIRE BLOCK 12"]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("shard-0.cursor")).unwrap(),
+            format!("12 {}\n", hex::encode([3; 32]))
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn block_replacing_the_cursor_block_is_written() {
+        let (mut outputs, dir) = outputs("replace");
+        outputs.write(&block(10, 1)).unwrap();
+
+        let mut restarted = ShardOutputs::new(dir.clone(), vec![], codec::HashEncoding::Hex);
+        restarted.write(&block(10, 2)).unwrap();
+
+        assert_eq!(
+            written_heights(&dir),
+            vec!["FIRE BLOCK 10", "FIRE BLOCK 10"]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn undo_rewinds_the_cursor() {
+        let (mut outputs, dir) = outputs("undo");
+        outputs.write(&block(10, 1)).unwrap();
+        outputs.write(&block(11, 2)).unwrap();
+
+        let violation = Violation::Fork {
+            last: block_ref(11, 2),
+            block: block_ref(11, 3),
+            parent: block_ref(9, 4),
+        };
+        outputs.write_marker(&Marker::Undo(&violation)).unwrap();
+        outputs.write(&block(11, 3)).unwrap();
+
+        assert_eq!(
+            written_heights(&dir),
+            vec![
+                "FIRE BLOCK 10",
+                "FIRE BLOCK 11",
+                "FIRE UNDO 11",
+                "FIRE BLOCK 11"
+            ]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_cursor_is_an_error() {
+        let (mut outputs, dir) = outputs("invalid");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("shard-0.cursor"), "10\n").unwrap();
+
+        assert_eq!(
+            outputs.write(&block(11, 1)).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
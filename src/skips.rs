@@ -0,0 +1,80 @@
+use crate::codec;
+use std::ops::Range;
+use tracing::info;
+
+/// Tracks the heights without a block, NEAR heights are not contiguous since a block
+/// producer missing its slot leaves its height empty. Counts are kept per epoch and
+/// reported under the `stats` target when the epoch changes.
+pub(crate) struct SkippedHeights {
+    epoch_id: Option<codec::CryptoHash>,
+    epoch_skipped: u64,
+    epoch_blocks: u64,
+}
+
+impl SkippedHeights {
+    pub fn new() -> Self {
+        SkippedHeights {
+            epoch_id: None,
+            epoch_skipped: 0,
+            epoch_blocks: 0,
+        }
+    }
+
+    /// Returns the heights skipped between the parent of `header` and `header` itself.
+    pub fn observe(&mut self, header: &codec::BlockHeader) -> Range<u64> {
+        if self.epoch_id != header.epoch_id {
+            if let Some(epoch_id) = &self.epoch_id {
+                info!(
+                    target: "stats",
+                    "Epoch {} ended with {} skipped heights over {} blocks",
+                    epoch_id,
+                    self.epoch_skipped,
+                    self.epoch_blocks,
+                );
+            }
+
+            self.epoch_id = header.epoch_id.clone();
+            self.epoch_skipped = 0;
+            self.epoch_blocks = 0;
+        }
+
+        let skipped = match header.prev_height {
+            0 => header.height..header.height,
+            prev_height => prev_height + 1..header.height,
+        };
+
+        self.epoch_skipped += skipped.end.saturating_sub(skipped.start);
+        self.epoch_blocks += 1;
+
+        skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(height: u64, prev_height: u64, epoch: u8) -> codec::BlockHeader {
+        codec::BlockHeader {
+            height,
+            prev_height,
+            epoch_id: Some(codec::CryptoHash {
+                bytes: vec![epoch; 32],
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn observes_skipped_heights_per_epoch() {
+        let mut skipped = SkippedHeights::new();
+
+        assert!(skipped.observe(&header(10, 0, 1)).is_empty());
+        assert!(skipped.observe(&header(11, 10, 1)).is_empty());
+        assert_eq!(skipped.observe(&header(14, 11, 1)), 12..14);
+        assert_eq!((skipped.epoch_skipped, skipped.epoch_blocks), (2, 3));
+
+        assert_eq!(skipped.observe(&header(16, 14, 2)), 15..16);
+        assert_eq!((skipped.epoch_skipped, skipped.epoch_blocks), (1, 1));
+    }
+}
@@ -0,0 +1,633 @@
+use crate::codec;
+use crate::events;
+use crate::filter::AccountPattern;
+use serde_json::Value;
+
+/// Receipt whose logs are looked at for token activity, with what is needed to
+/// qualify it.
+struct ReceiptLogs<'a> {
+    receipt_id: &'a Option<codec::CryptoHash>,
+    contract_id: &'a str,
+    method_name: Option<&'a str>,
+    status: codec::ReceiptStatus,
+    logs: &'a [String],
+}
+
+fn receipt_logs(shards: &[codec::IndexerShard]) -> impl Iterator<Item = ReceiptLogs<'_>> {
+    shards
+        .iter()
+        .flat_map(|shard| shard.receipt_execution_outcomes.iter())
+        .filter_map(|o| {
+            let outcome = o.execution_outcome.as_ref()?;
+            let execution_outcome = outcome.outcome.as_ref()?;
+
+            Some(ReceiptLogs {
+                receipt_id: &outcome.id,
+                contract_id: &execution_outcome.executor_id,
+                method_name: o.receipt.as_ref().and_then(method_name),
+                status: status(execution_outcome),
+                logs: &execution_outcome.logs,
+            })
+        })
+}
+
+/// Method of the first function call of an action receipt.
+///
+/// Logs are recorded per receipt, not per action, so a receipt batching calls to several
+/// methods has all its transfers qualified by the first one.
+fn method_name(receipt: &codec::Receipt) -> Option<&str> {
+    let Some(codec::receipt::Receipt::Action(action)) = &receipt.receipt else {
+        return None;
+    };
+
+    action.actions.iter().find_map(|a| match &a.action {
+        Some(codec::action::Action::FunctionCall(call)) => Some(call.method_name.as_str()),
+        _ => None,
+    })
+}
+
+fn status(outcome: &codec::ExecutionOutcome) -> codec::ReceiptStatus {
+    match outcome.status {
+        Some(codec::execution_outcome::Status::SuccessValue(_))
+        | Some(codec::execution_outcome::Status::SuccessReceiptId(_)) => {
+            codec::ReceiptStatus::Success
+        }
+        Some(codec::execution_outcome::Status::Failure(_)) => codec::ReceiptStatus::Failure,
+        Some(codec::execution_outcome::Status::Unknown(_)) | None => codec::ReceiptStatus::Unknown,
+    }
+}
+
+fn string_member(data: &Value, name: &str) -> String {
+    data.get(name)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn amount(amount: &str) -> Option<codec::BigInt> {
+    amount.parse::<u128>().ok().map(codec::BigInt::from)
+}
+
+/// Extracts fungible token transfers, mints and burns from the NEP-141 events logged by
+/// the receipts executed in `shards`. Receipts of `legacy_contracts` without NEP-141
+/// events are looked at for the logs of tokens predating NEP-297,
+/// `Transfer <amount> from <sender> to <receiver>`, `Refund <amount> from <receiver> to
+/// <sender>` and `Account @<owner> burned <amount>`, which any contract could log.
+pub(crate) fn extract_ft_transfers(
+    shards: &[codec::IndexerShard],
+    legacy_contracts: &[AccountPattern],
+) -> Vec<codec::FtTransfer> {
+    let mut transfers = vec![];
+
+    for receipt in receipt_logs(shards) {
+        let mut has_events = false;
+
+        for (log_index, log) in receipt.logs.iter().enumerate() {
+            let Some(event) = events::parse(log) else {
+                continue;
+            };
+            if event.standard != "nep141" {
+                continue;
+            }
+            has_events = true;
+
+            let entries = match &event.data {
+                Some(Value::Array(entries)) => entries.as_slice(),
+                _ => continue,
+            };
+            for data in entries {
+                let (kind, sender_id, receiver_id) = match event.event.as_str() {
+                    "ft_transfer" => (
+                        match receipt.method_name {
+                            Some("ft_transfer_call") => codec::FtTransferKind::TransferCall,
+                            Some("ft_resolve_transfer") => codec::FtTransferKind::Refund,
+                            _ => codec::FtTransferKind::Transfer,
+                        },
+                        string_member(data, "old_owner_id"),
+                        string_member(data, "new_owner_id"),
+                    ),
+                    "ft_mint" => (
+                        codec::FtTransferKind::Mint,
+                        String::new(),
+                        string_member(data, "owner_id"),
+                    ),
+                    "ft_burn" => (
+                        codec::FtTransferKind::Burn,
+                        string_member(data, "owner_id"),
+                        String::new(),
+                    ),
+                    _ => continue,
+                };
+
+                let Some(amount) = data.get("amount").and_then(Value::as_str).and_then(amount)
+                else {
+                    continue;
+                };
+
+                transfers.push(codec::FtTransfer {
+                    kind: kind.into(),
+                    contract_id: receipt.contract_id.to_string(),
+                    sender_id,
+                    receiver_id,
+                    amount: Some(amount),
+                    memo: string_member(data, "memo"),
+                    receipt_id: receipt.receipt_id.clone(),
+                    status: receipt.status.into(),
+                    log_index: log_index as u32,
+                });
+            }
+        }
+
+        let is_legacy = legacy_contracts
+            .iter()
+            .any(|pattern| pattern.matches(receipt.contract_id));
+        if has_events || !is_legacy {
+            continue;
+        }
+
+        for (log_index, log) in receipt.logs.iter().enumerate() {
+            let Some((kind, amount, sender_id, receiver_id)) = parse_legacy_log(log) else {
+                continue;
+            };
+
+            transfers.push(codec::FtTransfer {
+                kind: kind.into(),
+                contract_id: receipt.contract_id.to_string(),
+                sender_id,
+                receiver_id,
+                amount: Some(amount),
+                memo: String::new(),
+                receipt_id: receipt.receipt_id.clone(),
+                status: receipt.status.into(),
+                log_index: log_index as u32,
+            });
+        }
+    }
+
+    transfers
+}
+
+fn parse_legacy_log(log: &str) -> Option<(codec::FtTransferKind, codec::BigInt, String, String)> {
+    if let Some(rest) = log.strip_prefix("Account @") {
+        let (owner_id, amount_value) = rest.split_once(" burned ")?;
+        return Some((
+            codec::FtTransferKind::Burn,
+            amount(amount_value)?,
+            owner_id.to_string(),
+            String::new(),
+        ));
+    }
+
+    let (kind, rest) = if let Some(rest) = log.strip_prefix("Transfer ") {
+        (codec::FtTransferKind::Transfer, rest)
+    } else if let Some(rest) = log.strip_prefix("Refund ") {
+        (codec::FtTransferKind::Refund, rest)
+    } else {
+        return None;
+    };
+
+    let (amount_value, rest) = rest.split_once(" from ")?;
+    let (sender_id, receiver_id) = rest.split_once(" to ")?;
+    if sender_id.contains(' ') || receiver_id.contains(' ') {
+        return None;
+    }
+
+    Some((
+        kind,
+        amount(amount_value)?,
+        sender_id.to_string(),
+        receiver_id.to_string(),
+    ))
+}
+
+/// Extracts non-fungible token transfers, mints and burns from the NEP-171 events logged
+/// by the receipts executed in `shards`.
+pub(crate) fn extract_nft_activities(shards: &[codec::IndexerShard]) -> Vec<codec::NftActivity> {
+    let mut activities = vec![];
+
+    for receipt in receipt_logs(shards) {
+        for (log_index, log) in receipt.logs.iter().enumerate() {
+            let Some(event) = events::parse(log) else {
+                continue;
+            };
+            if event.standard != "nep171" {
+                continue;
+            }
+
+            let entries = match &event.data {
+                Some(Value::Array(entries)) => entries.as_slice(),
+                _ => continue,
+            };
+            for data in entries {
+                let (kind, old_owner_id, new_owner_id) = match event.event.as_str() {
+                    "nft_transfer" => (
+                        codec::NftActivityKind::Transfer,
+                        string_member(data, "old_owner_id"),
+                        string_member(data, "new_owner_id"),
+                    ),
+                    "nft_mint" => (
+                        codec::NftActivityKind::Mint,
+                        String::new(),
+                        string_member(data, "owner_id"),
+                    ),
+                    "nft_burn" => (
+                        codec::NftActivityKind::Burn,
+                        string_member(data, "owner_id"),
+                        String::new(),
+                    ),
+                    _ => continue,
+                };
+
+                let token_ids = match data.get("token_ids") {
+                    Some(Value::Array(ids)) => ids
+                        .iter()
+                        .filter_map(|id| id.as_str().map(String::from))
+                        .collect(),
+                    _ => continue,
+                };
+
+                activities.push(codec::NftActivity {
+                    kind: kind.into(),
+                    contract_id: receipt.contract_id.to_string(),
+                    token_ids,
+                    old_owner_id,
+                    new_owner_id,
+                    authorized_id: string_member(data, "authorized_id"),
+                    memo: string_member(data, "memo"),
+                    receipt_id: receipt.receipt_id.clone(),
+                    status: receipt.status.into(),
+                    log_index: log_index as u32,
+                });
+            }
+        }
+    }
+
+    activities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::execution_outcome::Status;
+
+    fn shard(
+        contract_id: &str,
+        method_name: &str,
+        status: Status,
+        logs: &[&str],
+    ) -> codec::IndexerShard {
+        let call = codec::FunctionCallAction {
+            method_name: method_name.to_string(),
+            ..Default::default()
+        };
+        let receipt = codec::Receipt {
+            receiver_id: contract_id.to_string(),
+            receipt: Some(codec::receipt::Receipt::Action(codec::ReceiptAction {
+                actions: vec![codec::Action {
+                    action: Some(codec::action::Action::FunctionCall(call)),
+                }],
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        codec::IndexerShard {
+            receipt_execution_outcomes: vec![codec::IndexerExecutionOutcomeWithReceipt {
+                execution_outcome: Some(codec::ExecutionOutcomeWithId {
+                    outcome: Some(codec::ExecutionOutcome {
+                        logs: logs.iter().map(|log| log.to_string()).collect(),
+                        executor_id: contract_id.to_string(),
+                        status: Some(status),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                receipt: Some(receipt),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn success() -> Status {
+        Status::SuccessValue(Default::default())
+    }
+
+    fn event(standard: &str, event: &str, data: Value) -> String {
+        let log = serde_json::json!({
+            "standard": standard,
+            "version": "1.0.0",
+            "event": event,
+            "data": [data],
+        });
+        format!("EVENT_JSON:{}", log)
+    }
+
+    fn legacy_contracts() -> Vec<AccountPattern> {
+        ["wrap.near", "*.factory.bridge.near"]
+            .iter()
+            .map(|p| p.parse().unwrap())
+            .collect()
+    }
+
+    fn ft_transfers(shard: codec::IndexerShard) -> Vec<codec::FtTransfer> {
+        extract_ft_transfers(&[shard], &legacy_contracts())
+    }
+
+    #[test]
+    fn nep141_transfers() {
+        let transfer = event(
+            "nep141",
+            "ft_transfer",
+            serde_json::json!({
+                "old_owner_id": "alice.near",
+                "new_owner_id": "bob.near",
+                "amount": "10",
+                "memo": "rent",
+            }),
+        );
+
+        for (method_name, kind) in [
+            ("ft_transfer", codec::FtTransferKind::Transfer),
+            ("ft_transfer_call", codec::FtTransferKind::TransferCall),
+            ("ft_resolve_transfer", codec::FtTransferKind::Refund),
+        ] {
+            let shard = shard("token.near", method_name, success(), &["Memo", &transfer]);
+            assert_eq!(
+                ft_transfers(shard),
+                [codec::FtTransfer {
+                    kind: kind.into(),
+                    contract_id: "token.near".to_string(),
+                    sender_id: "alice.near".to_string(),
+                    receiver_id: "bob.near".to_string(),
+                    amount: Some(codec::BigInt::from(10u128)),
+                    memo: "rent".to_string(),
+                    receipt_id: None,
+                    status: codec::ReceiptStatus::Success.into(),
+                    log_index: 1,
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn nep141_mints_and_burns() {
+        let mint = event(
+            "nep141",
+            "ft_mint",
+            serde_json::json!({"owner_id": "alice.near", "amount": "5"}),
+        );
+        let burn = event(
+            "nep141",
+            "ft_burn",
+            serde_json::json!({"owner_id": "bob.near", "amount": "3"}),
+        );
+        let failure = Status::Failure(Default::default());
+
+        let transfers = ft_transfers(shard("token.near", "mint", failure, &[&mint, &burn]));
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].kind, codec::FtTransferKind::Mint as i32);
+        assert_eq!(
+            (
+                transfers[0].sender_id.as_str(),
+                transfers[0].receiver_id.as_str()
+            ),
+            ("", "alice.near")
+        );
+        assert_eq!(transfers[1].kind, codec::FtTransferKind::Burn as i32);
+        assert_eq!(
+            (
+                transfers[1].sender_id.as_str(),
+                transfers[1].receiver_id.as_str()
+            ),
+            ("bob.near", "")
+        );
+        assert_eq!(transfers[1].amount, Some(codec::BigInt::from(3u128)));
+        assert!(transfers
+            .iter()
+            .all(|t| t.status == codec::ReceiptStatus::Failure as i32));
+    }
+
+    #[test]
+    fn nep141_entries_without_a_valid_amount_are_skipped() {
+        let logs = [
+            event(
+                "nep141",
+                "ft_mint",
+                serde_json::json!({"owner_id": "alice.near"}),
+            ),
+            event(
+                "nep141",
+                "ft_mint",
+                serde_json::json!({"owner_id": "alice.near", "amount": "-1"}),
+            ),
+            event(
+                "nep171",
+                "nft_mint",
+                serde_json::json!({"owner_id": "alice.near", "token_ids": ["1"]}),
+            ),
+        ];
+        let logs: Vec<&str> = logs.iter().map(String::as_str).collect();
+
+        assert!(ft_transfers(shard("token.near", "mint", success(), &logs)).is_empty());
+    }
+
+    #[test]
+    fn legacy_logs_of_allowlisted_contracts() {
+        let logs = [
+            "Transfer 10 from alice.near to bob.near",
+            "Refund 4 from bob.near to alice.near",
+            "Account @carol.near burned 2",
+            "Transfer 10 from alice.near to bob.near and more",
+        ];
+
+        let transfers = ft_transfers(shard("wrap.near", "ft_transfer", success(), &logs));
+        let summary: Vec<_> = transfers
+            .iter()
+            .map(|t| {
+                (
+                    codec::FtTransferKind::from_i32(t.kind).unwrap(),
+                    t.sender_id.as_str(),
+                    t.receiver_id.as_str(),
+                    t.log_index,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (codec::FtTransferKind::Transfer, "alice.near", "bob.near", 0),
+                (codec::FtTransferKind::Refund, "bob.near", "alice.near", 1),
+                (codec::FtTransferKind::Burn, "carol.near", "", 2),
+            ]
+        );
+
+        let bridged = shard(
+            "usdc.factory.bridge.near",
+            "ft_transfer",
+            success(),
+            &logs[..1],
+        );
+        assert_eq!(ft_transfers(bridged).len(), 1);
+    }
+
+    #[test]
+    fn legacy_logs_are_ignored_from_other_contracts_and_alongside_events() {
+        let legacy = "Transfer 10 from alice.near to bob.near";
+        assert!(ft_transfers(shard("token.near", "ft_transfer", success(), &[legacy])).is_empty());
+
+        let mint = event(
+            "nep141",
+            "ft_mint",
+            serde_json::json!({"owner_id": "alice.near", "amount": "5"}),
+        );
+        let transfers = ft_transfers(shard(
+            "wrap.near",
+            "near_deposit",
+            success(),
+            &[&mint, legacy],
+        ));
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].kind, codec::FtTransferKind::Mint as i32);
+    }
+
+    fn nft_activities(logs: &[String]) -> Vec<codec::NftActivity> {
+        let logs: Vec<&str> = logs.iter().map(String::as_str).collect();
+        extract_nft_activities(&[shard("nft.near", "nft_batch", success(), &logs)])
+    }
+
+    /// Kind, old owner, new owner and authorized account of each activity.
+    fn owners(
+        activities: &[codec::NftActivity],
+    ) -> Vec<(codec::NftActivityKind, &str, &str, &str)> {
+        activities
+            .iter()
+            .map(|a| {
+                (
+                    codec::NftActivityKind::from_i32(a.kind).unwrap(),
+                    a.old_owner_id.as_str(),
+                    a.new_owner_id.as_str(),
+                    a.authorized_id.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nep171_mints() {
+        let logs = [
+            event(
+                "nep171",
+                "nft_mint",
+                serde_json::json!({"owner_id": "alice.near", "token_ids": ["1", "2"]}),
+            ),
+            event(
+                "nep171",
+                "nft_mint",
+                serde_json::json!({
+                    "owner_id": "bob.near",
+                    "token_ids": ["3"],
+                    "authorized_id": "minter.near",
+                    "memo": "drop",
+                }),
+            ),
+        ];
+
+        let activities = nft_activities(&logs);
+        assert_eq!(
+            activities[0],
+            codec::NftActivity {
+                kind: codec::NftActivityKind::Mint.into(),
+                contract_id: "nft.near".to_string(),
+                token_ids: vec!["1".to_string(), "2".to_string()],
+                old_owner_id: String::new(),
+                new_owner_id: "alice.near".to_string(),
+                authorized_id: String::new(),
+                memo: String::new(),
+                receipt_id: None,
+                status: codec::ReceiptStatus::Success.into(),
+                log_index: 0,
+            }
+        );
+        assert_eq!(activities[1].new_owner_id, "bob.near");
+        assert_eq!(activities[1].authorized_id, "minter.near");
+        assert_eq!(activities[1].memo, "drop");
+        assert_eq!(activities[1].log_index, 1);
+    }
+
+    #[test]
+    fn nep171_transfers() {
+        let logs = [
+            event(
+                "nep171",
+                "nft_transfer",
+                serde_json::json!({
+                    "old_owner_id": "alice.near",
+                    "new_owner_id": "bob.near",
+                    "token_ids": ["1"],
+                }),
+            ),
+            event(
+                "nep171",
+                "nft_transfer",
+                serde_json::json!({
+                    "old_owner_id": "bob.near",
+                    "new_owner_id": "carol.near",
+                    "token_ids": ["1"],
+                    "authorized_id": "market.near",
+                }),
+            ),
+        ];
+
+        assert_eq!(
+            owners(&nft_activities(&logs)),
+            [
+                (
+                    codec::NftActivityKind::Transfer,
+                    "alice.near",
+                    "bob.near",
+                    ""
+                ),
+                (
+                    codec::NftActivityKind::Transfer,
+                    "bob.near",
+                    "carol.near",
+                    "market.near"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn nep171_burns() {
+        let logs = [
+            event(
+                "nep171",
+                "nft_burn",
+                serde_json::json!({"owner_id": "alice.near", "token_ids": ["1"]}),
+            ),
+            event(
+                "nep171",
+                "nft_burn",
+                serde_json::json!({
+                    "owner_id": "bob.near",
+                    "token_ids": ["2"],
+                    "authorized_id": "burner.near",
+                }),
+            ),
+            event(
+                "nep171",
+                "nft_burn",
+                serde_json::json!({"owner_id": "bob.near"}),
+            ),
+        ];
+
+        assert_eq!(
+            owners(&nft_activities(&logs)),
+            [
+                (codec::NftActivityKind::Burn, "alice.near", "", ""),
+                (codec::NftActivityKind::Burn, "bob.near", "", "burner.near"),
+            ]
+        );
+    }
+}
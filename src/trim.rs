@@ -0,0 +1,361 @@
+use crate::codec;
+use crate::codec::execution_outcome::Status;
+use crate::filter::AccountPattern;
+use serde::{Deserialize, Deserializer};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Byte limits of trimmable payloads. In contract overrides an absent limit falls back
+/// to the default one while `null` lifts it. Negative limits are rejected, a limit of 0
+/// empties the payload, keeping only its hash and length.
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Limits {
+    #[serde(default, deserialize_with = "explicit")]
+    function_call_args: Option<Option<usize>>,
+    #[serde(default, deserialize_with = "explicit")]
+    logs: Option<Option<usize>>,
+    #[serde(default, deserialize_with = "explicit")]
+    receipt_data: Option<Option<usize>>,
+    #[serde(default, deserialize_with = "explicit")]
+    success_value: Option<Option<usize>>,
+    /// Accepts limits of 0.
+    #[serde(default)]
+    allow_empty: bool,
+}
+
+/// Tells an explicit `null` apart from an absent field.
+fn explicit<'de, D>(deserializer: D) -> Result<Option<Option<usize>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<usize>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    #[serde(default)]
+    default: Limits,
+    #[serde(default)]
+    contracts: HashMap<String, Limits>,
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    FunctionCallArgs,
+    Logs,
+    ReceiptData,
+    SuccessValue,
+}
+
+impl Limits {
+    /// Rejects limits of 0, which would blank the payloads rather than trim them, unless
+    /// `allow_empty` is set.
+    fn check(&self) -> Result<(), String> {
+        if self.allow_empty {
+            return Ok(());
+        }
+        let fields = [
+            ("function_call_args", self.function_call_args),
+            ("logs", self.logs),
+            ("receipt_data", self.receipt_data),
+            ("success_value", self.success_value),
+        ];
+        match fields.iter().find(|(_, limit)| *limit == Some(Some(0))) {
+            Some((name, _)) => Err(format!(
+                "{} limit of 0 empties the payloads, set allow_empty to keep it",
+                name
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn get(&self, field: Field) -> Option<Option<usize>> {
+        match field {
+            Field::FunctionCallArgs => self.function_call_args,
+            Field::Logs => self.logs,
+            Field::ReceiptData => self.receipt_data,
+            Field::SuccessValue => self.success_value,
+        }
+    }
+}
+
+/// Trims function call args, logs, receipt data and success values beyond their byte
+/// limit, keeping the SHA-256 and length of the full payload next to it.
+///
+/// Limits are read from a JSON file with `default` limits and per contract `contracts`
+/// overrides, keyed by account or `*.` sub-accounts pattern, exact accounts taking
+/// precedence over patterns and longer patterns over shorter ones:
+///
+/// ```json
+/// {
+///   "default": {"function_call_args": 1024, "logs": 4096, "success_value": 1024},
+///   "contracts": {"aurora": {"function_call_args": null}, "*.sweat": {"logs": 256}}
+/// }
+/// ```
+///
+/// Payloads are attributed to the receiver of their action or data receipt and to the
+/// executor of their execution outcome, values of finalized transactions to the
+/// transaction receiver.
+pub(crate) struct PayloadTrimmer {
+    default: Limits,
+    contracts: Vec<(AccountPattern, Limits)>,
+}
+
+impl PayloadTrimmer {
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read(path).map_err(|err| err.to_string())?;
+        let config: Config = serde_json::from_slice(&json).map_err(|err| err.to_string())?;
+
+        let mut contracts = config
+            .contracts
+            .into_iter()
+            .map(|(pattern, limits)| Ok((pattern.parse::<AccountPattern>()?, limits)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let overrides = contracts.iter().map(|(_, limits)| limits);
+        for limits in std::iter::once(&config.default).chain(overrides) {
+            limits.check()?;
+        }
+        contracts.sort_by_key(|(pattern, _)| match pattern {
+            AccountPattern::Exact(_) => usize::MAX,
+            AccountPattern::SubAccounts(suffix) => suffix.len(),
+        });
+        contracts.reverse();
+
+        Ok(PayloadTrimmer {
+            default: config.default,
+            contracts,
+        })
+    }
+
+    /// Limit of the most specific override matching the account, limits it omits being
+    /// the default ones rather than those of less specific overrides.
+    fn limit(&self, account_id: &str, field: Field) -> Option<usize> {
+        self.contracts
+            .iter()
+            .find(|(pattern, _)| pattern.matches(account_id))
+            .and_then(|(_, limits)| limits.get(field))
+            .unwrap_or_else(|| self.default.get(field).flatten())
+    }
+
+    pub fn apply(&self, block: &mut codec::Block) {
+        for shard in block.shards.iter_mut() {
+            if let Some(chunk) = shard.chunk.as_mut() {
+                for tx in chunk.transactions.iter_mut() {
+                    if let Some(signed) = tx.transaction.as_mut() {
+                        self.trim_actions(&signed.receiver_id, &mut signed.actions);
+                    }
+                    if let Some(outcome) = tx.outcome.as_mut() {
+                        self.trim_outcome(&mut outcome.execution_outcome);
+                        if let Some(receipt) = outcome.receipt.as_mut() {
+                            self.trim_receipt(receipt);
+                        }
+                    }
+                }
+                for receipt in chunk.receipts.iter_mut() {
+                    self.trim_receipt(receipt);
+                }
+            }
+
+            for outcome in shard.receipt_execution_outcomes.iter_mut() {
+                self.trim_outcome(&mut outcome.execution_outcome);
+                if let Some(receipt) = outcome.receipt.as_mut() {
+                    self.trim_receipt(receipt);
+                }
+            }
+        }
+
+        for tx in block.finalized_transactions.iter_mut() {
+            let limit = self.limit(&tx.receiver_id, Field::SuccessValue);
+            tx.value_trimmed = trim_bytes(&mut tx.value, limit);
+        }
+    }
+
+    fn trim_actions(&self, receiver_id: &str, actions: &mut [codec::Action]) {
+        for action in actions {
+            match action.action.as_mut() {
+                Some(codec::action::Action::FunctionCall(call)) => {
+                    let limit = self.limit(receiver_id, Field::FunctionCallArgs);
+                    call.args_trimmed = trim_bytes(&mut call.args, limit);
+                }
+                Some(codec::action::Action::Delegate(signed)) => {
+                    if let Some(delegate) = signed.delegate_action.as_mut() {
+                        self.trim_actions(&delegate.receiver_id, &mut delegate.actions);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn trim_receipt(&self, receipt: &mut codec::Receipt) {
+        match receipt.receipt.as_mut() {
+            Some(codec::receipt::Receipt::Action(action)) => {
+                self.trim_actions(&receipt.receiver_id, &mut action.actions)
+            }
+            Some(codec::receipt::Receipt::Data(data)) => {
+                if let Some(bytes) = data.data.as_mut() {
+                    let limit = self.limit(&receipt.receiver_id, Field::ReceiptData);
+                    data.data_trimmed = trim_bytes(bytes, limit);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn trim_outcome(&self, outcome: &mut Option<codec::ExecutionOutcomeWithId>) {
+        let Some(outcome) = outcome.as_mut().and_then(|o| o.outcome.as_mut()) else {
+            return;
+        };
+
+        if let Some(limit) = self.limit(&outcome.executor_id, Field::Logs) {
+            outcome.trimmed_logs = outcome
+                .logs
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(index, log)| {
+                    trim_string(log, limit).map(|payload| codec::TrimmedLog {
+                        index: index as u32,
+                        payload: Some(payload),
+                    })
+                })
+                .collect();
+        }
+
+        if let Some(Status::SuccessValue(success)) = outcome.status.as_mut() {
+            let limit = self.limit(&outcome.executor_id, Field::SuccessValue);
+            success.value_trimmed = trim_bytes(&mut success.value, limit);
+        }
+    }
+}
+
+fn trimmed(payload: &[u8]) -> codec::TrimmedPayload {
+    codec::TrimmedPayload {
+        hash: Sha256::digest(payload).to_vec(),
+        original_length: payload.len() as u64,
+    }
+}
+
+fn trim_bytes(bytes: &mut Vec<u8>, limit: Option<usize>) -> Option<codec::TrimmedPayload> {
+    let limit = limit?;
+    if bytes.len() <= limit {
+        return None;
+    }
+
+    let trimmed = trimmed(bytes);
+    bytes.truncate(limit);
+    Some(trimmed)
+}
+
+/// Trims a string on the last character boundary within the limit.
+fn trim_string(s: &mut String, limit: usize) -> Option<codec::TrimmedPayload> {
+    if s.len() <= limit {
+        return None;
+    }
+
+    let trimmed = trimmed(s.as_bytes());
+    let mut end = limit;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    s.truncate(end);
+    Some(trimmed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn trimmer(config: serde_json::Value) -> Result<PayloadTrimmer, String> {
+        static CONFIGS: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "trim-config-{}-{}.json",
+            std::process::id(),
+            CONFIGS.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, config.to_string()).unwrap();
+        let trimmer = PayloadTrimmer::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        trimmer
+    }
+
+    #[test]
+    fn most_specific_override_wins() {
+        let trimmer = trimmer(serde_json::json!({
+            "default": {"logs": 100, "success_value": 10},
+            "contracts": {
+                "*.near": {"logs": 50, "success_value": 5},
+                "*.app.near": {"logs": 20},
+                "api.app.near": {"logs": null},
+            },
+        }))
+        .unwrap();
+
+        assert_eq!(trimmer.limit("bob.org", Field::Logs), Some(100));
+        assert_eq!(trimmer.limit("bob.near", Field::Logs), Some(50));
+        assert_eq!(trimmer.limit("web.app.near", Field::Logs), Some(20));
+        assert_eq!(trimmer.limit("api.app.near", Field::Logs), None);
+
+        // Omitted limits are the default ones, not those of `*.near`.
+        assert_eq!(trimmer.limit("bob.near", Field::SuccessValue), Some(5));
+        assert_eq!(trimmer.limit("web.app.near", Field::SuccessValue), Some(10));
+        assert_eq!(trimmer.limit("api.app.near", Field::SuccessValue), Some(10));
+        assert_eq!(trimmer.limit("api.app.near", Field::FunctionCallArgs), None);
+    }
+
+    #[test]
+    fn empty_limits_need_allow_empty() {
+        let err = trimmer(serde_json::json!({"contracts": {"aurora": {"logs": 0}}}))
+            .err()
+            .unwrap();
+        assert!(err.contains("logs limit of 0"), "{}", err);
+
+        let trimmer = trimmer(serde_json::json!({
+            "default": {"function_call_args": 0, "allow_empty": true},
+        }))
+        .unwrap();
+        let mut action = codec::Action {
+            action: Some(codec::action::Action::FunctionCall(
+                codec::FunctionCallAction {
+                    args: b"{}".to_vec(),
+                    ..Default::default()
+                },
+            )),
+        };
+        trimmer.trim_actions("aurora", std::slice::from_mut(&mut action));
+
+        let Some(codec::action::Action::FunctionCall(call)) = action.action else {
+            panic!("function call expected");
+        };
+        assert!(call.args.is_empty());
+        assert_eq!(call.args_trimmed, Some(trimmed(b"{}")));
+    }
+
+    #[test]
+    fn negative_limits_are_rejected() {
+        assert!(trimmer(serde_json::json!({"default": {"logs": -1}})).is_err());
+    }
+
+    #[test]
+    fn strings_are_trimmed_on_a_character_boundary() {
+        let mut log = "aé€".to_string();
+        assert_eq!(trim_string(&mut log, 6), None);
+
+        // `€` takes bytes 3 to 5, a limit of 5 or 4 backs off to the end of `é`.
+        for limit in [5, 4] {
+            let mut log = "aé€".to_string();
+            let payload = trim_string(&mut log, limit).unwrap();
+            assert_eq!(log, "aé");
+            assert_eq!(payload, trimmed("aé€".as_bytes()));
+            assert_eq!(payload.original_length, 6);
+        }
+
+        let mut log = "aé€".to_string();
+        trim_string(&mut log, 2);
+        assert_eq!(log, "a");
+    }
+}
@@ -0,0 +1,78 @@
+use near_indexer::near_primitives::hash::CryptoHash;
+use near_indexer::near_primitives::types::ShardId;
+use std::fmt::{Display, Formatter};
+
+pub(crate) mod outcomes;
+pub(crate) mod roots;
+
+/// What to do when a block's content does not match its commitments.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum VerificationPolicy {
+    /// Do not verify anything
+    Off,
+    /// Verify and log failures, the block is still emitted
+    Count,
+    /// Verify and stop the indexer on the first failure
+    Reject,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Failure {
+    /// A block header root is not the Merkle root of the matching chunk headers root.
+    BlockRoot {
+        root: &'static str,
+        expected: CryptoHash,
+        computed: CryptoHash,
+    },
+    /// A chunk `outgoing_receipts_root` does not match the receipts of the chunk.
+    ReceiptsRoot {
+        shard_id: ShardId,
+        expected: CryptoHash,
+        computed: CryptoHash,
+    },
+    /// An execution outcome proof does not lead to the `outcome_root` of the chunk that
+    /// committed it.
+    OutcomeProof {
+        shard_id: ShardId,
+        outcome_id: CryptoHash,
+        outcome_root: CryptoHash,
+    },
+    /// The block could not be checked, e.g. its shard layout could not be fetched. It
+    /// is neither a mismatch nor a reason to reject the block.
+    Unverifiable(String),
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::BlockRoot {
+                root,
+                expected,
+                computed,
+            } => write!(
+                f,
+                "block {} is {} but chunk headers merklize to {}",
+                root, expected, computed
+            ),
+            Failure::ReceiptsRoot {
+                shard_id,
+                expected,
+                computed,
+            } => write!(
+                f,
+                "shard {} outgoing receipts root is {} but receipts merklize to {}",
+                shard_id, expected, computed
+            ),
+            Failure::OutcomeProof {
+                shard_id,
+                outcome_id,
+                outcome_root,
+            } => write!(
+                f,
+                "outcome {} proof does not lead to shard {} outcome root {}",
+                outcome_id, shard_id, outcome_root
+            ),
+            Failure::Unverifiable(reason) => write!(f, "block is unverifiable, {}", reason),
+        }
+    }
+}
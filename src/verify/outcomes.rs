@@ -0,0 +1,243 @@
+use super::Failure;
+use near_indexer::near_primitives::hash::CryptoHash;
+use near_indexer::near_primitives::merkle::{merklize, verify_path, MerklePath};
+use near_indexer::near_primitives::types::ShardId;
+use near_indexer::near_primitives::views::ExecutionOutcomeWithIdView;
+use near_indexer::StreamerMessage;
+use std::collections::HashMap;
+
+struct PendingOutcome {
+    id: CryptoHash,
+    hashes: Vec<CryptoHash>,
+    proof: MerklePath,
+}
+
+/// Outcomes of a shard waiting for its next new chunk, along with the last block seen
+/// since they were produced.
+struct PendingOutcomes {
+    block_hash: CryptoHash,
+    outcomes: Vec<PendingOutcome>,
+}
+
+/// Verifies execution outcome proofs. The outcomes produced by applying a chunk are
+/// committed in the `outcome_root` of the next new chunk of the same shard, so the
+/// outcomes of a block are kept until that chunk is seen and checked against it.
+///
+/// Pending outcomes are dropped when a block does not extend the last block seen, the
+/// chain forked and they belong to the abandoned branch.
+pub(crate) struct OutcomeVerifier {
+    pending: HashMap<ShardId, PendingOutcomes>,
+}
+
+impl OutcomeVerifier {
+    pub fn new() -> Self {
+        OutcomeVerifier {
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn verify(&mut self, sm: &StreamerMessage) -> Vec<Failure> {
+        let header = &sm.block.header;
+        let mut failures = vec![];
+
+        let chunk_outcome_roots: Vec<CryptoHash> =
+            sm.block.chunks.iter().map(|c| c.outcome_root).collect();
+        let (computed, _) = merklize(&chunk_outcome_roots);
+        if computed != header.outcome_root {
+            failures.push(Failure::BlockRoot {
+                root: "outcome_root",
+                expected: header.outcome_root,
+                computed,
+            });
+        }
+
+        self.follow(header.prev_hash, header.hash);
+
+        for chunk in &sm.block.chunks {
+            if chunk.height_included != header.height {
+                continue;
+            }
+
+            failures.extend(self.check(chunk.shard_id, chunk.outcome_root));
+        }
+
+        for shard in &sm.shards {
+            let transaction_outcomes = shard
+                .chunk
+                .iter()
+                .flat_map(|c| c.transactions.iter())
+                .map(|tx| &tx.outcome.execution_outcome);
+            let receipt_outcomes = shard
+                .receipt_execution_outcomes
+                .iter()
+                .map(|r| &r.execution_outcome);
+
+            self.record(
+                shard.shard_id,
+                header.hash,
+                transaction_outcomes.chain(receipt_outcomes),
+            );
+        }
+
+        failures
+    }
+
+    /// Moves the pending outcomes to the block `hash`, dropping those of blocks its
+    /// parent `prev_hash` does not descend from.
+    fn follow(&mut self, prev_hash: CryptoHash, hash: CryptoHash) {
+        self.pending
+            .retain(|_, pending| pending.block_hash == prev_hash);
+        for pending in self.pending.values_mut() {
+            pending.block_hash = hash;
+        }
+    }
+
+    /// Checks the outcomes pending for `shard_id` against the `outcome_root` of its new
+    /// chunk.
+    fn check(&mut self, shard_id: ShardId, outcome_root: CryptoHash) -> Vec<Failure> {
+        let outcomes = match self.pending.remove(&shard_id) {
+            Some(pending) => pending.outcomes,
+            None => return vec![],
+        };
+
+        outcomes
+            .into_iter()
+            .filter(|outcome| !verify_path(outcome_root, &outcome.proof, &outcome.hashes))
+            .map(|outcome| Failure::OutcomeProof {
+                shard_id,
+                outcome_id: outcome.id,
+                outcome_root,
+            })
+            .collect()
+    }
+
+    fn record<'a>(
+        &mut self,
+        shard_id: ShardId,
+        block_hash: CryptoHash,
+        outcomes: impl Iterator<Item = &'a ExecutionOutcomeWithIdView>,
+    ) {
+        let outcomes: Vec<PendingOutcome> = outcomes.map(PendingOutcome::from).collect();
+        if !outcomes.is_empty() {
+            self.pending.insert(
+                shard_id,
+                PendingOutcomes {
+                    block_hash,
+                    outcomes,
+                },
+            );
+        }
+    }
+}
+
+impl From<&ExecutionOutcomeWithIdView> for PendingOutcome {
+    fn from(o: &ExecutionOutcomeWithIdView) -> Self {
+        PendingOutcome {
+            id: o.id,
+            hashes: o.to_hashes(),
+            proof: o.proof.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_indexer::near_primitives::views::{
+        ExecutionMetadataView, ExecutionOutcomeView, ExecutionStatusView,
+    };
+
+    fn outcome(id: u8, logs: &[&str]) -> ExecutionOutcomeWithIdView {
+        ExecutionOutcomeWithIdView {
+            proof: vec![],
+            block_hash: CryptoHash([1; 32]),
+            id: CryptoHash([id; 32]),
+            outcome: ExecutionOutcomeView {
+                logs: logs.iter().map(|log| log.to_string()).collect(),
+                receipt_ids: vec![CryptoHash([id + 100; 32])],
+                gas_burnt: 2_428_000_000_000,
+                tokens_burnt: 242_800_000_000_000_000_000,
+                executor_id: "alice.near".parse().unwrap(),
+                status: ExecutionStatusView::SuccessValue(vec![]),
+                metadata: ExecutionMetadataView {
+                    version: 1,
+                    gas_profile: None,
+                },
+            },
+        }
+    }
+
+    /// Outcomes with the proofs of the chunk `outcome_root` they are committed in.
+    fn committed(
+        outcomes: Vec<ExecutionOutcomeWithIdView>,
+    ) -> (CryptoHash, Vec<ExecutionOutcomeWithIdView>) {
+        let hashes: Vec<Vec<CryptoHash>> = outcomes.iter().map(|o| o.to_hashes()).collect();
+        let (root, paths) = merklize(&hashes);
+
+        let outcomes = outcomes
+            .into_iter()
+            .zip(paths)
+            .map(|(outcome, proof)| ExecutionOutcomeWithIdView { proof, ..outcome })
+            .collect();
+
+        (root, outcomes)
+    }
+
+    #[test]
+    fn valid_proofs_pass() {
+        let (root, outcomes) = committed(vec![
+            outcome(1, &["a"]),
+            outcome(2, &[]),
+            outcome(3, &["c"]),
+        ]);
+        let mut verifier = OutcomeVerifier::new();
+        verifier.record(0, CryptoHash([9; 32]), outcomes.iter());
+
+        assert_eq!(verifier.check(0, root), vec![]);
+        assert!(verifier.pending.is_empty());
+    }
+
+    #[test]
+    fn tampered_outcome_fails() {
+        let (root, mut outcomes) = committed(vec![outcome(1, &["a"]), outcome(2, &["b"])]);
+        outcomes[1].outcome.logs.push("injected".to_string());
+        let mut verifier = OutcomeVerifier::new();
+        verifier.record(0, CryptoHash([9; 32]), outcomes.iter());
+
+        assert_eq!(
+            verifier.check(0, root),
+            vec![Failure::OutcomeProof {
+                shard_id: 0,
+                outcome_id: CryptoHash([2; 32]),
+                outcome_root: root,
+            }]
+        );
+    }
+
+    #[test]
+    fn outcomes_of_forked_blocks_are_dropped() {
+        let (root, mut outcomes) = committed(vec![outcome(1, &["a"])]);
+        outcomes[0].outcome.logs.clear();
+        let mut verifier = OutcomeVerifier::new();
+        verifier.record(0, CryptoHash([9; 32]), outcomes.iter());
+        verifier.record(1, CryptoHash([9; 32]), outcomes.iter());
+
+        verifier.follow(CryptoHash([9; 32]), CryptoHash([10; 32]));
+        assert_eq!(verifier.pending.len(), 2);
+
+        // The next block forks off below the last one seen.
+        verifier.follow(CryptoHash([8; 32]), CryptoHash([11; 32]));
+        assert_eq!(verifier.check(0, root), vec![]);
+        assert!(verifier.pending.is_empty());
+    }
+
+    #[test]
+    fn outcomes_of_other_shards_are_kept() {
+        let (root, outcomes) = committed(vec![outcome(1, &["a"])]);
+        let mut verifier = OutcomeVerifier::new();
+        verifier.record(1, CryptoHash([9; 32]), outcomes.iter());
+
+        assert_eq!(verifier.check(0, root), vec![]);
+        assert_eq!(verifier.check(1, root), vec![]);
+    }
+}
@@ -0,0 +1,315 @@
+//! Recomputes the transaction and receipt Merkle roots of a streamed block.
+//!
+//! Roots are computed from the nearcore views, before the block is converted, as the
+//! conversion does not keep everything receipts are hashed with. Chunk `tx_root` cannot
+//! be recomputed, transactions are hashed with the `block_hash` they were signed against
+//! and the views do not carry it, so transactions are only covered through the block
+//! `chunk_tx_root`.
+
+use super::Failure;
+use near_client::{GetProtocolConfig, ViewClientActor};
+use near_indexer::near_primitives::hash::CryptoHash;
+use near_indexer::near_primitives::merkle::merklize;
+use near_indexer::near_primitives::receipt::Receipt;
+use near_indexer::near_primitives::shard_layout::{account_id_to_shard_id, ShardLayout};
+use near_indexer::near_primitives::types::{BlockId, BlockReference, ShardId};
+use near_indexer::{IndexerChunkView, StreamerMessage};
+use near_o11y::WithSpanContextExt;
+use std::collections::HashSet;
+
+pub(crate) struct RootVerifier {
+    view_client: actix::Addr<ViewClientActor>,
+    shard_layout: Option<(CryptoHash, ShardLayout)>,
+}
+
+impl RootVerifier {
+    pub fn new(view_client: actix::Addr<ViewClientActor>) -> Self {
+        RootVerifier {
+            view_client,
+            shard_layout: None,
+        }
+    }
+
+    pub async fn verify(&mut self, sm: &StreamerMessage) -> Vec<Failure> {
+        let header = &sm.block.header;
+        let chunks = &sm.block.chunks;
+        let mut failures = vec![];
+
+        check_block_root(
+            &mut failures,
+            "chunk_tx_root",
+            header.chunk_tx_root,
+            chunks.iter().map(|c| c.tx_root).collect(),
+        );
+        check_block_root(
+            &mut failures,
+            "chunk_receipts_root",
+            header.chunk_receipts_root,
+            chunks.iter().map(|c| c.outgoing_receipts_root).collect(),
+        );
+
+        let shard_layout = match self.shard_layout(header.epoch_id, header.hash).await {
+            Ok(shard_layout) => shard_layout,
+            Err(failure) => {
+                failures.push(failure);
+                return failures;
+            }
+        };
+
+        for chunk in sm.shards.iter().filter_map(|s| s.chunk.as_ref()) {
+            match outgoing_receipts_root(chunk, shard_layout) {
+                Ok(computed) if computed != chunk.header.outgoing_receipts_root => {
+                    failures.push(Failure::ReceiptsRoot {
+                        shard_id: chunk.header.shard_id,
+                        expected: chunk.header.outgoing_receipts_root,
+                        computed,
+                    })
+                }
+                Ok(_) => {}
+                Err(failure) => failures.push(failure),
+            }
+        }
+
+        failures
+    }
+
+    /// Shard layout of the block's epoch, the one chunk producers group outgoing
+    /// receipts with. It is fetched once per epoch.
+    async fn shard_layout(
+        &mut self,
+        epoch_id: CryptoHash,
+        block_hash: CryptoHash,
+    ) -> Result<&ShardLayout, Failure> {
+        if !matches!(&self.shard_layout, Some((id, _)) if *id == epoch_id) {
+            let config = self
+                .view_client
+                .send(
+                    GetProtocolConfig(BlockReference::BlockId(BlockId::Hash(block_hash)))
+                        .with_span_context(),
+                )
+                .await
+                .map_err(|err| Failure::Unverifiable(format!("no shard layout, {}", err)))?
+                .map_err(|err| Failure::Unverifiable(format!("no shard layout, {}", err)))?;
+            self.shard_layout = Some((epoch_id, config.shard_layout));
+        }
+
+        Ok(&self.shard_layout.as_ref().unwrap().1)
+    }
+}
+
+fn check_block_root(
+    failures: &mut Vec<Failure>,
+    root: &'static str,
+    expected: CryptoHash,
+    leaves: Vec<CryptoHash>,
+) {
+    let (computed, _) = merklize(&leaves);
+    if computed != expected {
+        failures.push(Failure::BlockRoot {
+            root,
+            expected,
+            computed,
+        });
+    }
+}
+
+/// Receipts are grouped by receiving shard, in shard layout order, and each group is
+/// hashed along with its shard id. Local receipts, appended to the chunk receipts by
+/// the indexer, never leave the shard and are not part of the commitment.
+fn outgoing_receipts_root(
+    chunk: &IndexerChunkView,
+    shard_layout: &ShardLayout,
+) -> Result<CryptoHash, Failure> {
+    let local_receipt_ids: HashSet<&CryptoHash> = chunk
+        .transactions
+        .iter()
+        .filter(|tx| tx.transaction.signer_id == tx.transaction.receiver_id)
+        .filter_map(|tx| tx.outcome.execution_outcome.outcome.receipt_ids.first())
+        .collect();
+
+    let mut receipts: Vec<(ShardId, Vec<Receipt>)> =
+        shard_layout.shard_ids().map(|id| (id, vec![])).collect();
+    for view in chunk.receipts.iter() {
+        if local_receipt_ids.contains(&view.receipt_id) {
+            continue;
+        }
+
+        let receipt = Receipt::try_from(view.clone()).map_err(|err| {
+            Failure::Unverifiable(format!(
+                "receipt {} not convertible, {}",
+                view.receipt_id, err
+            ))
+        })?;
+        let shard_id = account_id_to_shard_id(receipt.receiver_id(), shard_layout);
+        if let Some((_, shard_receipts)) = receipts.iter_mut().find(|(id, _)| *id == shard_id) {
+            shard_receipts.push(receipt);
+        }
+    }
+
+    let hashes: Vec<CryptoHash> = receipts
+        .iter()
+        .map(|(shard_id, shard_receipts)| CryptoHash::hash_borsh(&(*shard_id, shard_receipts)))
+        .collect();
+
+    Ok(merklize(&hashes).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_chain::Chain;
+    use serde_json::{json, Value};
+
+    fn hash(byte: u8) -> String {
+        CryptoHash([byte; 32]).to_string()
+    }
+
+    fn public_key() -> String {
+        format!("ed25519:{}", bs58::encode([1u8; 32]).into_string())
+    }
+
+    fn receipt(id: u8, receiver_id: &str) -> Value {
+        json!({
+            "predecessor_id": "alice.near",
+            "receiver_id": receiver_id,
+            "receipt_id": hash(id),
+            "receipt": {
+                "Action": {
+                    "signer_id": "alice.near",
+                    "signer_public_key": public_key(),
+                    "gas_price": "100000000",
+                    "output_data_receivers": [],
+                    "input_data_ids": [],
+                    "actions": [{"Transfer": {"deposit": id.to_string()}}],
+                    "is_promise_yield": false
+                }
+            },
+            "priority": 0
+        })
+    }
+
+    /// Chunk whose receipts go to several shards of the simple nightshade layout,
+    /// along with the local receipt of a transaction the indexer appends to them.
+    fn chunk() -> IndexerChunkView {
+        let signature = format!("ed25519:{}", bs58::encode([2u8; 64]).into_string());
+
+        serde_json::from_value(json!({
+            "author": "validator.near",
+            "header": {
+                "chunk_hash": hash(1),
+                "prev_block_hash": hash(0),
+                "outcome_root": hash(0),
+                "prev_state_root": hash(0),
+                "encoded_merkle_root": hash(0),
+                "encoded_length": 0,
+                "height_created": 100,
+                "height_included": 100,
+                "shard_id": 0,
+                "gas_used": 0,
+                "gas_limit": 1000000000000000u64,
+                "rent_paid": "0",
+                "validator_reward": "0",
+                "balance_burnt": "0",
+                "outgoing_receipts_root": hash(0),
+                "tx_root": hash(0),
+                "validator_proposals": [],
+                "congestion_info": null,
+                "signature": signature
+            },
+            "transactions": [{
+                "transaction": {
+                    "signer_id": "bob.near",
+                    "public_key": public_key(),
+                    "nonce": 1,
+                    "receiver_id": "bob.near",
+                    "priority_fee": 0,
+                    "actions": [],
+                    "signature": signature,
+                    "hash": hash(2)
+                },
+                "outcome": {
+                    "execution_outcome": {
+                        "proof": [],
+                        "block_hash": hash(0),
+                        "id": hash(2),
+                        "outcome": {
+                            "logs": [],
+                            "receipt_ids": [hash(14)],
+                            "gas_burnt": 0,
+                            "tokens_burnt": "0",
+                            "executor_id": "bob.near",
+                            "status": {"SuccessReceiptId": hash(14)},
+                            "metadata": {"version": 1, "gas_profile": null}
+                        }
+                    },
+                    "receipt": null
+                }
+            }],
+            "receipts": [
+                receipt(10, "alice.near"),
+                receipt(11, "aurora"),
+                receipt(12, "zoe.near"),
+                receipt(13, "alice.near"),
+                receipt(14, "bob.near"),
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn outgoing_receipts_root_matches_nearcore() {
+        let shard_layout = ShardLayout::get_simple_nightshade_layout();
+        let chunk = chunk();
+        let outgoing: Vec<Receipt> = chunk.receipts[..4]
+            .iter()
+            .map(|view| Receipt::try_from(view.clone()).unwrap())
+            .collect();
+        let (expected, _) = merklize(&Chain::build_receipts_hashes(&outgoing, &shard_layout));
+
+        assert_eq!(outgoing_receipts_root(&chunk, &shard_layout), Ok(expected));
+    }
+
+    #[test]
+    fn tampered_receipt_changes_outgoing_receipts_root() {
+        let shard_layout = ShardLayout::get_simple_nightshade_layout();
+        let expected = outgoing_receipts_root(&chunk(), &shard_layout).unwrap();
+
+        let mut tampered = chunk();
+        tampered.receipts[1].predecessor_id = "mallory.near".parse().unwrap();
+        assert_ne!(
+            outgoing_receipts_root(&tampered, &shard_layout),
+            Ok(expected)
+        );
+
+        // The local receipt is not part of the commitment.
+        let mut local = chunk();
+        local.receipts[4].predecessor_id = "mallory.near".parse().unwrap();
+        assert_eq!(outgoing_receipts_root(&local, &shard_layout), Ok(expected));
+    }
+
+    #[test]
+    fn block_root_of_chunk_roots() {
+        let leaves = vec![
+            CryptoHash([1; 32]),
+            CryptoHash([2; 32]),
+            CryptoHash([3; 32]),
+        ];
+        let (root, _) = merklize(&leaves);
+
+        let mut failures = vec![];
+        check_block_root(&mut failures, "chunk_tx_root", root, leaves.clone());
+        assert_eq!(failures, vec![]);
+
+        let mut tampered = leaves;
+        tampered[2] = CryptoHash([4; 32]);
+        check_block_root(&mut failures, "chunk_tx_root", root, tampered.clone());
+        assert_eq!(
+            failures,
+            vec![Failure::BlockRoot {
+                root: "chunk_tx_root",
+                expected: root,
+                computed: merklize(&tampered).0,
+            }]
+        );
+    }
+}
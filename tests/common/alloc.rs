@@ -0,0 +1,88 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// System allocator counting allocations, meant to be installed as the global allocator.
+pub struct CountingAllocator {
+    allocations: AtomicUsize,
+    allocated: AtomicUsize,
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+/// Allocations made by a measured closure.
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub allocations: usize,
+    /// Total of the bytes allocated, freed or not.
+    pub allocated: usize,
+    /// Highest number of live bytes above the ones live before the closure.
+    pub peak: usize,
+}
+
+impl CountingAllocator {
+    pub const fn new() -> Self {
+        CountingAllocator {
+            allocations: AtomicUsize::new(0),
+            allocated: AtomicUsize::new(0),
+            current: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    /// Stats of the allocations made by `f`. Allocations of other threads are counted
+    /// too, tests measuring allocations must not run concurrently.
+    pub fn measure<T>(&self, f: impl FnOnce() -> T) -> (T, Stats) {
+        let current = self.current.load(Ordering::SeqCst);
+        self.allocations.store(0, Ordering::SeqCst);
+        self.allocated.store(0, Ordering::SeqCst);
+        self.peak.store(current, Ordering::SeqCst);
+
+        let value = f();
+        let stats = Stats {
+            allocations: self.allocations.load(Ordering::SeqCst),
+            allocated: self.allocated.load(Ordering::SeqCst),
+            peak: self.peak.load(Ordering::SeqCst).saturating_sub(current),
+        };
+
+        (value, stats)
+    }
+
+    fn on_alloc(&self, size: usize) {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        self.allocated.fetch_add(size, Ordering::Relaxed);
+        let current = self.current.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak.fetch_max(current, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.on_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.on_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.current.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            self.current.fetch_sub(layout.size(), Ordering::Relaxed);
+            self.on_alloc(new_size);
+        }
+        new_ptr
+    }
+}
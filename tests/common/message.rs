@@ -0,0 +1,233 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use near_indexer::StreamerMessage;
+use serde_json::{json, Value};
+
+/// Shape of a synthetic streamer message.
+#[derive(Clone, Copy)]
+pub struct Shape {
+    pub shards: u64,
+    /// Function call transactions per shard, each with its executed receipt.
+    pub transactions: usize,
+    /// Size of the function call args.
+    pub args_size: usize,
+    /// Contract deployments per shard, each with a code of `code_size` bytes.
+    pub deployments: usize,
+    pub code_size: usize,
+    /// Block producer approvals, every other one missing.
+    pub approvals: usize,
+    /// Validator proposals in the block header and in each chunk header.
+    pub proposals: usize,
+}
+
+fn hash(seed: u64) -> String {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    bs58::encode(bytes).into_string()
+}
+
+fn public_key() -> String {
+    format!("ed25519:{}", bs58::encode([1u8; 32]).into_string())
+}
+
+fn signature() -> String {
+    format!("ed25519:{}", bs58::encode([2u8; 64]).into_string())
+}
+
+fn proposals(count: usize) -> Vec<Value> {
+    (0..count)
+        .map(|i| {
+            json!({
+                "validator_stake_struct_version": "V1",
+                "account_id": format!("validator-{}.near", i),
+                "public_key": public_key(),
+                "stake": "100000000000000000000000000000"
+            })
+        })
+        .collect()
+}
+
+fn payload(size: usize, seed: u64) -> String {
+    let bytes: Vec<u8> = (0..size).map(|i| (i as u64 ^ seed) as u8).collect();
+    STANDARD.encode(bytes)
+}
+
+fn outcome(id: u64, executor_id: &str) -> Value {
+    json!({
+        "proof": [],
+        "block_hash": hash(0),
+        "id": hash(id),
+        "outcome": {
+            "logs": [format!("EVENT_JSON:{{\"standard\":\"nep141\",\"id\":{}}}", id)],
+            "receipt_ids": [hash(id + 1)],
+            "gas_burnt": 2428000000000u64,
+            "tokens_burnt": "242800000000000000000",
+            "executor_id": executor_id,
+            "status": {"SuccessValue": ""},
+            "metadata": {"version": 1, "gas_profile": null}
+        }
+    })
+}
+
+fn action_receipt(id: u64, actions: Vec<Value>) -> Value {
+    json!({
+        "predecessor_id": "alice.near",
+        "receiver_id": "contract.near",
+        "receipt_id": hash(id),
+        "receipt": {
+            "Action": {
+                "signer_id": "alice.near",
+                "signer_public_key": public_key(),
+                "gas_price": "100000000",
+                "output_data_receivers": [],
+                "input_data_ids": [],
+                "actions": actions,
+                "is_promise_yield": false
+            }
+        },
+        "priority": 0
+    })
+}
+
+fn shard(shard_id: u64, shape: &Shape) -> Value {
+    let seed = shard_id << 32;
+    let call = |i: u64| {
+        json!({
+            "FunctionCall": {
+                "method_name": "ft_transfer",
+                "args": payload(shape.args_size, seed + i),
+                "gas": 30000000000000u64,
+                "deposit": "1"
+            }
+        })
+    };
+    let deploy = |i: u64| json!({"DeployContract": {"code": payload(shape.code_size, seed + i)}});
+
+    let transactions: Vec<Value> = (0..shape.transactions as u64)
+        .map(|i| {
+            json!({
+                "transaction": {
+                    "signer_id": "alice.near",
+                    "public_key": public_key(),
+                    "nonce": i,
+                    "receiver_id": "contract.near",
+                    "priority_fee": 0,
+                    "actions": [call(i)],
+                    "signature": signature(),
+                    "hash": hash(seed + i)
+                },
+                "outcome": {"execution_outcome": outcome(seed + i, "alice.near"), "receipt": null}
+            })
+        })
+        .collect();
+    let receipts: Vec<Value> = (0..shape.deployments as u64)
+        .map(|i| action_receipt(seed + 1_000_000 + i, vec![deploy(i)]))
+        .collect();
+    let executed: Vec<Value> = (0..shape.transactions as u64)
+        .map(|i| {
+            json!({
+                "execution_outcome": outcome(seed + 2_000_000 + i, "contract.near"),
+                "receipt": action_receipt(seed + 2_000_000 + i, vec![call(i)])
+            })
+        })
+        .collect();
+
+    json!({
+        "shard_id": shard_id,
+        "chunk": {
+            "author": "validator.near",
+            "header": chunk_header(shard_id, shape),
+            "transactions": transactions,
+            "receipts": receipts
+        },
+        "receipt_execution_outcomes": executed,
+        "state_changes": []
+    })
+}
+
+fn chunk_header(shard_id: u64, shape: &Shape) -> Value {
+    json!({
+        "chunk_hash": hash(shard_id),
+        "prev_block_hash": hash(0),
+        "outcome_root": hash(0),
+        "prev_state_root": hash(0),
+        "encoded_merkle_root": hash(0),
+        "encoded_length": 0,
+        "height_created": 100,
+        "height_included": 100,
+        "shard_id": shard_id,
+        "gas_used": 0,
+        "gas_limit": 1000000000000000u64,
+        "rent_paid": "0",
+        "validator_reward": "0",
+        "balance_burnt": "0",
+        "outgoing_receipts_root": hash(0),
+        "tx_root": hash(0),
+        "validator_proposals": proposals(shape.proposals),
+        "congestion_info": null,
+        "signature": signature()
+    })
+}
+
+fn header(shape: &Shape) -> Value {
+    let shards = shape.shards;
+    let approvals: Vec<Value> = (0..shape.approvals)
+        .map(|i| match i % 2 {
+            0 => json!(signature()),
+            _ => Value::Null,
+        })
+        .collect();
+
+    json!({
+        "height": 100,
+        "prev_height": 99,
+        "epoch_id": hash(0),
+        "next_epoch_id": hash(0),
+        "hash": hash(100),
+        "prev_hash": hash(99),
+        "prev_state_root": hash(0),
+        "block_body_hash": null,
+        "chunk_receipts_root": hash(0),
+        "chunk_headers_root": hash(0),
+        "chunk_tx_root": hash(0),
+        "outcome_root": hash(0),
+        "chunks_included": shards,
+        "challenges_root": hash(0),
+        "timestamp": 1700000000000000000u64,
+        "timestamp_nanosec": "1700000000000000000",
+        "random_value": hash(0),
+        "validator_proposals": proposals(shape.proposals),
+        "chunk_mask": vec![true; shards as usize],
+        "gas_price": "100000000",
+        "block_ordinal": 100,
+        "rent_paid": "0",
+        "validator_reward": "0",
+        "total_supply": "1000000000000000000000000000000000",
+        "challenges_result": [],
+        "last_final_block": hash(98),
+        "last_ds_final_block": hash(99),
+        "next_bp_hash": hash(0),
+        "block_merkle_root": hash(0),
+        "epoch_sync_data_hash": null,
+        "approvals": approvals,
+        "signature": signature(),
+        "latest_protocol_version": 69,
+        "chunk_endorsements": null
+    })
+}
+
+/// Streamer message of the given shape, its hashes and signatures being made up.
+pub fn message(shape: Shape) -> StreamerMessage {
+    let message = json!({
+        "block": {
+            "author": "validator.near",
+            "header": header(&shape),
+            "chunks": (0..shape.shards)
+                .map(|id| chunk_header(id, &shape))
+                .collect::<Vec<_>>()
+        },
+        "shards": (0..shape.shards).map(|id| shard(id, &shape)).collect::<Vec<_>>()
+    });
+
+    serde_json::from_value(message).expect("synthetic streamer message is valid")
+}
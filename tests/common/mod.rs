@@ -0,0 +1,7 @@
+//! Helpers shared by the integration tests and the benches, which include this module by
+//! path as the indexer has no library target.
+
+#![allow(dead_code)]
+
+pub mod alloc;
+pub mod message;
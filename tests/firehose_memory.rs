@@ -0,0 +1,113 @@
+//! Peak memory of `FIRE BLOCK` lines written for multi-megabyte blocks, which must stay
+//! close to the size of the encoded block and never reach the size of its text form.
+
+#[allow(dead_code, unused_imports)]
+#[path = "../src/codec/mod.rs"]
+mod codec;
+mod common;
+#[allow(dead_code)]
+#[path = "../src/continuity.rs"]
+mod continuity;
+#[allow(dead_code)]
+#[path = "../src/firehose.rs"]
+mod firehose;
+
+use common::alloc::CountingAllocator;
+use common::message::{message, Shape};
+use firehose::Output;
+use prost::Message;
+use std::io;
+use std::sync::Mutex;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator::new();
+
+/// Allocations are counted process wide, measured tests must not run concurrently.
+static SERIAL: Mutex<()> = Mutex::new(());
+
+const MIB: usize = 1024 * 1024;
+
+/// A block of 4 shards each deploying two contracts of 2 MiB.
+fn large_block() -> codec::Block {
+    codec::Block::from(message(Shape {
+        shards: 4,
+        transactions: 50,
+        args_size: 256,
+        deployments: 2,
+        code_size: 2 * MIB,
+        approvals: 2,
+        proposals: 0,
+    }))
+}
+
+#[test]
+fn hex_output_peak_stays_below_text_size() {
+    let _serial = SERIAL.lock().unwrap();
+    let block = large_block();
+    let header = block.header.clone().unwrap();
+    let encoded_len = block.encoded_len();
+    assert!(encoded_len > 16 * MIB);
+
+    let mut output = Output::new(io::sink());
+    let (result, first) = ALLOCATOR.measure(|| output.write_block(&header, &block));
+    result.unwrap();
+    // The encoded block buffer, plus the line header and slack, the hex text being
+    // twice the encoded size.
+    assert!(
+        first.peak < encoded_len + MIB,
+        "peak {} for a {} bytes block",
+        first.peak,
+        encoded_len
+    );
+
+    let (result, second) = ALLOCATOR.measure(|| output.write_block(&header, &block));
+    result.unwrap();
+    // The block buffer is shrunk to its retained size, the next block only grows it.
+    assert!(
+        second.peak < encoded_len - 15 * MIB + MIB,
+        "peak {} for a {} bytes block written again",
+        second.peak,
+        encoded_len
+    );
+}
+
+#[test]
+fn compressed_output_peak_stays_below_text_size() {
+    let _serial = SERIAL.lock().unwrap();
+    let block = large_block();
+    let header = block.header.clone().unwrap();
+    let encoded_len = block.encoded_len();
+
+    let mut output = Output::new(io::sink());
+    output.set_compressor(codec::BlockCompressor::new(3, None).unwrap());
+    let (result, stats) = ALLOCATOR.measure(|| output.write_block(&header, &block));
+    result.unwrap();
+    // The encoded block and its compressed copy at worst, plus the zstd context, the
+    // base64 text being a third larger than the compressed block.
+    assert!(
+        stats.peak < 2 * encoded_len + 4 * MIB,
+        "peak {} for a {} bytes block",
+        stats.peak,
+        encoded_len
+    );
+}
+
+#[test]
+fn header_only_output_does_not_encode_the_block() {
+    let _serial = SERIAL.lock().unwrap();
+    let block = large_block();
+    let header = block.header.clone().unwrap();
+    let header_only_block = codec::HeaderOnlyBlock {
+        header: block.header.clone(),
+        chunk_headers: block.chunk_headers.clone(),
+    };
+
+    let mut output = Output::new(io::sink());
+    let (result, stats) = ALLOCATOR.measure(|| output.write_block(&header, &header_only_block));
+    result.unwrap();
+    assert!(
+        stats.peak < 64 * 1024,
+        "peak {} for a header only block",
+        stats.peak
+    );
+}